@@ -0,0 +1,40 @@
+/* -*- coding: utf8 -*-
+ *
+ *  len.rs: benchmarks counting and expanding nodes
+ *
+ *  (C) Copyright 2022 - 2023 Olivier Delhomme
+ *  e-mail : olivier.delhomme@free.fr
+ *
+ *  This program is free software; you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation; either version 3, or (at your option)
+ *  any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with this program; if not, write to the Free Software Foundation,
+ *  Inc., 59 Temple Place - Suite 330, Boston, MA 02111-1307, USA.
+ */
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use nodeset::{Node, NodeSet};
+
+fn bench_len(c: &mut Criterion) {
+    let node = Node::new("node[1-1000000000]-cpu[1-64]").unwrap();
+    c.bench_function("node len", |b| b.iter(|| black_box(&node).len()));
+
+    let nodeset = NodeSet::new("node[1-1000000000],gpu[1-4000000000/3]").unwrap();
+    c.bench_function("nodeset len", |b| b.iter(|| black_box(&nodeset).len()));
+}
+
+fn bench_expand(c: &mut Criterion) {
+    let nodeset = NodeSet::new("rack[1-10]-node[1-100]").unwrap();
+    c.bench_function("nodeset expand", |b| b.iter(|| black_box(&nodeset).expand(",")));
+}
+
+criterion_group!(benches, bench_len, bench_expand);
+criterion_main!(benches);
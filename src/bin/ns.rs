@@ -20,7 +20,7 @@
  *  Inc., 59 Temple Place - Suite 330, Boston, MA 02111-1307, USA.
  */
 
-use clap::{Args, Parser, Subcommand};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 /// rack[10-49]node[1-25/2,78-89,101,1001].panel[0-30/4]
 /// Between ',' a Range :
 /// * 10-49
@@ -29,10 +29,14 @@ use clap::{Args, Parser, Subcommand};
 /// * 101,
 /// * 1001
 /// * 0-30/4
+///
 /// Between '[]' a Set
+///
 /// A global name 'rack{}node{}.panel{}' and a vector of sets.
 use nodeset::NodeSet;
 use std::error::Error;
+use std::fmt::Display;
+use std::io::{stderr, stdout, Write};
 use std::process::exit;
 
 // This structure holds arguments provided to the program from the command line.
@@ -41,6 +45,11 @@ use std::process::exit;
 #[command(author, version, about, long_about = None)]
 #[command(propagate_version = true)]
 struct Arguments {
+    /// does not print error messages (the exit code still tells that
+    /// something went wrong)
+    #[arg(short, long, global = true)]
+    quiet: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -50,6 +59,14 @@ enum Commands {
     Count(Count),
     Expand(Expand),
     Fold(Fold),
+    Check(Check),
+    Contains(Contains),
+    /// folds the nodes that are in all the nodesets
+    Intersection(Operands),
+    /// folds the nodes of the first nodeset that are in none of the others
+    Difference(Operands),
+    /// folds the nodes of all the nodesets
+    Union(Operands),
 }
 
 /// counts the number of nodes in nodeset(s).
@@ -58,6 +75,10 @@ struct Count {
     /// sums all nodes of every given nodesets as it was one nodeset
     #[arg(short, long)]
     total: bool,
+    /// prints a running sum of the nodes after each given nodeset. The last
+    /// printed value is the total (only this one is printed with `--total`).
+    #[arg(short, long)]
+    cumulative: bool,
     nodesets: Vec<String>,
 }
 
@@ -69,80 +90,470 @@ struct Expand {
     #[arg(default_value_t = ' ')]
     separator: char,
 
+    /// stops after expanding this number of nodes
+    #[arg(short, long)]
+    limit: Option<u64>,
+
+    /// groups digits of numbers by three in the output (`node1000000` is
+    /// displayed `node1_000_000`)
+    #[arg(short, long)]
+    group_digits: bool,
+
+    /// character used to group digits with `--group-digits`
+    #[arg(long, default_value_t = '_')]
+    group_separator: char,
+
     nodesets: Vec<String>,
 }
 
 /// Folds nodeset(s) into a synthetic notation
 #[derive(Args, Debug)]
 struct Fold {
+    /// how to print each nodeset
+    #[arg(short, long, value_enum, default_value_t = Format::Brackets)]
+    format: Format,
+
+    nodesets: Vec<String>,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum Format {
+    /// folded nodeset: node[1-3]
+    Brackets,
+    /// comma-separated list of nodes: node1,node2,node3
+    List,
+    /// JSON array of nodes: ["node1","node2","node3"]
+    Json,
+}
+
+/// checks that nodeset(s) are correctly written without expanding them
+#[derive(Args, Debug)]
+struct Check {
+    nodesets: Vec<String>,
+}
+
+/// tells whether a node is in nodeset(s): the exit code is 0 when it is
+/// in at least one of them and 1 otherwise.
+#[derive(Args, Debug)]
+struct Contains {
+    hostname: String,
     nodesets: Vec<String>,
 }
 
-fn count(count: &Count) {
+/// nodesets combined from left to right
+#[derive(Args, Debug)]
+struct Operands {
+    #[arg(required = true)]
+    nodesets: Vec<String>,
+}
+
+// Returns the values that `count` subcommand has to print
+fn count_values(count: &Count) -> Result<Vec<u64>, Box<dyn Error>> {
+    let mut values = Vec::new();
     let mut total = 0;
+
     for node_str in &count.nodesets {
-        let node = match NodeSet::new(node_str) {
-            Ok(n) => n,
-            Err(e) => {
-                eprintln!("Error: {e}");
-                exit(1);
-            }
-        };
-        if count.total {
-            total += node.len();
-        } else {
-            println!("{}", node.len());
+        let node = NodeSet::new(node_str)?;
+        total += node.len();
+        match (count.total, count.cumulative) {
+            (true, _) => (),
+            (false, true) => values.push(total),
+            (false, false) => values.push(node.len()),
         }
     }
     if count.total {
-        println!("{total}");
+        values.push(total);
     }
+
+    Ok(values)
 }
 
-fn expand(expand: &Expand) -> Result<(), Box<dyn Error>> {
-    let separator = &expand.separator;
+// Writes the error message to `out` unless `quiet` is set
+fn print_error<W: Write>(out: &mut W, quiet: bool, error: impl Display) {
+    if !quiet {
+        // Nothing more can be done if the error can not be written
+        let _ = writeln!(out, "Error: {error}");
+    }
+}
+
+fn count(count: &Count) -> Result<(), Box<dyn Error>> {
+    for value in count_values(count)? {
+        println!("{value}");
+    }
+    Ok(())
+}
 
-    for node_str in &expand.nodesets {
+// Expands the nodeset with at most `limit` nodes when a limit is given.
+// Nodes after the limit are never generated.
+fn expand_nodeset(node: &NodeSet, separator: &str, limit: Option<u64>) -> Result<String, Box<dyn Error>> {
+    match limit {
+        Some(limit) => Ok(node.preview(usize::try_from(limit).unwrap_or(usize::MAX)).join(separator)),
+        None => node.expand(separator),
+    }
+}
+
+// Inserts `separator` every three digits (from the right) in all numbers
+// of `expanded` that have more than three digits.
+fn group_digits(expanded: &str, separator: char) -> String {
+    let mut grouped = String::with_capacity(expanded.len());
+    let mut digits = String::new();
+
+    let flush = |digits: &mut String, grouped: &mut String| {
+        for (i, d) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                grouped.push(separator);
+            }
+            grouped.push(d);
+        }
+        digits.clear();
+    };
+
+    for c in expanded.chars() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+        } else {
+            flush(&mut digits, &mut grouped);
+            grouped.push(c);
+        }
+    }
+    flush(&mut digits, &mut grouped);
+
+    grouped
+}
+
+// Writes the expanded nodesets into `out`. The truncation notice goes to
+// stderr so that `out` only holds node names.
+fn expand<W: Write>(out: &mut W, expand: &Expand, quiet: bool) -> Result<(), Box<dyn Error>> {
+    let separator = format!("{}", expand.separator);
+    let mut remaining = expand.limit;
+
+    for (index, node_str) in expand.nodesets.iter().enumerate() {
         let node = match NodeSet::new(node_str) {
             Ok(n) => n,
             Err(e) => return Err(Box::new(e)),
         };
-        match node.expand(format!("{separator}").as_str()) {
-            Ok(s) => println!("{s}"),
-            Err(e) => eprintln!("Error while expanding nodeset {node}: {e}"),
+        if remaining == Some(0) {
+            eprintln!("... (truncated)");
+            break;
+        }
+        match expand_nodeset(&node, &separator, remaining) {
+            Ok(s) if expand.group_digits => writeln!(out, "{}", group_digits(&s, expand.group_separator))?,
+            Ok(s) => writeln!(out, "{s}")?,
+            Err(e) => print_error(&mut stderr(), quiet, format!("while expanding nodeset {node}: {e}")),
         };
+        if let Some(limit) = remaining {
+            let len = node.len();
+            if len >= limit {
+                if len > limit || index + 1 < expand.nodesets.len() {
+                    eprintln!("... (truncated)");
+                }
+                break;
+            }
+            remaining = Some(limit - len);
+        }
     }
     Ok(())
 }
 
-fn fold(fold: &Fold) {
+// Writes `name` as a JSON string into `json`: quotes, backslashes and
+// control characters (U+0000 to U+001F) are escaped.
+fn push_json_string(json: &mut String, name: &str) {
+    json.push('"');
+    for c in name.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            c if c < ' ' => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
+
+// Returns the nodeset written in `format`
+fn format_nodeset(node: NodeSet, format: Format) -> Result<String, Box<dyn Error>> {
+    match format {
+        Format::Brackets => Ok(node.to_string()),
+        Format::List => node.expand(","),
+        Format::Json => {
+            let mut json = String::from("[");
+            for (index, name) in node.enumerate() {
+                if index > 0 {
+                    json.push(',');
+                }
+                push_json_string(&mut json, &name);
+            }
+            json.push(']');
+            Ok(json)
+        }
+    }
+}
+
+fn fold(fold: &Fold) -> Result<(), Box<dyn Error>> {
     for node_str in &fold.nodesets {
-        let node = match NodeSet::new(node_str) {
-            Ok(n) => n,
+        let node = NodeSet::new(node_str)?;
+        println!("{}", format_nodeset(node, fold.format)?);
+    }
+    Ok(())
+}
+
+// Prints OK or the parse error of each nodeset and returns the exit
+// code of the program: 1 when at least one nodeset is malformed.
+fn check(check: &Check, quiet: bool) -> i32 {
+    let mut code = 0;
+
+    for node_str in &check.nodesets {
+        match NodeSet::new(node_str) {
+            Ok(_) => println!("{node_str}: OK"),
             Err(e) => {
-                eprintln!("Error: {e}");
-                exit(1);
+                print_error(&mut stderr(), quiet, format!("{node_str}: {e}"));
+                code = 1;
             }
-        };
-        println!("{node}");
+        }
+    }
+    code
+}
+
+// Parses the nodesets and combines them from left to right with
+// `operation`: `a b c` gives `operation(operation(a, b), c)`.
+fn combine(operands: &Operands, operation: fn(&NodeSet, &NodeSet) -> NodeSet) -> Result<NodeSet, Box<dyn Error>> {
+    let mut nodesets = operands.nodesets.iter().map(NodeSet::new);
+    let mut result = match nodesets.next() {
+        Some(first) => first?,
+        None => return Err("no nodeset given".into()),
+    };
+
+    for nodeset in nodesets {
+        result = operation(&result, &nodeset?);
+    }
+    Ok(result.optimize())
+}
+
+// Writes into `out` (unless `quiet` is set) whether the hostname is in
+// each nodeset and returns true if it is in at least one of them.
+fn contains<W: Write>(out: &mut W, contains: &Contains, quiet: bool) -> Result<bool, Box<dyn Error>> {
+    let mut found = false;
+
+    for node_str in &contains.nodesets {
+        let node = NodeSet::new(node_str)?;
+        let is_in = node.contains(&contains.hostname);
+        if !quiet {
+            let verb = if is_in {
+                "is"
+            } else {
+                "is not"
+            };
+            writeln!(out, "{} {verb} in {node}", contains.hostname)?;
+        }
+        found = found || is_in;
     }
+    Ok(found)
 }
 
 fn main() {
     let args = Arguments::parse();
 
-    match &args.command {
-        Commands::Count(c) => {
-            count(c);
-        }
-        Commands::Expand(e) => {
-            if let Err(e) = expand(e) {
-                eprintln!("Error: {e}");
-                exit(1);
-            }
-        }
-        Commands::Fold(f) => {
-            fold(f);
-        }
+    let result = match &args.command {
+        Commands::Count(c) => count(c),
+        Commands::Expand(e) => expand(&mut stdout(), e, args.quiet),
+        Commands::Fold(f) => fold(f),
+        Commands::Check(c) => exit(check(c, args.quiet)),
+        Commands::Intersection(o) => combine(o, NodeSet::intersection).map(|n| println!("{n}")),
+        Commands::Difference(o) => combine(o, NodeSet::difference).map(|n| println!("{n}")),
+        Commands::Union(o) => combine(o, NodeSet::union).map(|n| println!("{n}")),
+        Commands::Contains(c) => contains(&mut stdout(), c, args.quiet).map(|found| exit(i32::from(!found))),
+    };
+
+    if let Err(e) = result {
+        print_error(&mut stderr(), args.quiet, e);
+        exit(1);
+    }
+}
+
+#[test]
+fn test_count_cumulative() {
+    let nodesets = vec!["node[1-10]".to_string(), "gpu[1-4]".to_string(), "node[11-12]".to_string()];
+
+    let mut count = Count {
+        total: false,
+        cumulative: false,
+        nodesets,
+    };
+    assert_eq!(count_values(&count).unwrap(), vec![10, 4, 2]);
+
+    count.cumulative = true;
+    assert_eq!(count_values(&count).unwrap(), vec![10, 14, 16]);
+
+    // --total prints only the final sum even with --cumulative
+    count.total = true;
+    assert_eq!(count_values(&count).unwrap(), vec![16]);
+
+    count.cumulative = false;
+    assert_eq!(count_values(&count).unwrap(), vec![16]);
+}
+
+#[test]
+fn test_expand_limit() {
+    let node = NodeSet::new("node[1-1000]").unwrap();
+    assert_eq!(expand_nodeset(&node, " ", Some(5)).unwrap(), "node1 node2 node3 node4 node5".to_string());
+    assert_eq!(expand_nodeset(&node, ",", Some(5)).unwrap().split(',').count(), 5);
+
+    let node = NodeSet::new("node[1-3]").unwrap();
+    assert_eq!(expand_nodeset(&node, " ", Some(5)).unwrap(), "node1 node2 node3".to_string());
+    assert_eq!(expand_nodeset(&node, " ", None).unwrap(), "node1 node2 node3".to_string());
+
+    let expanded = |arguments: &[&str]| {
+        let args = Arguments::parse_from(arguments);
+        let Commands::Expand(e) = args.command else {
+            panic!("expand subcommand expected")
+        };
+        let mut out: Vec<u8> = Vec::new();
+        expand(&mut out, &e, false).unwrap();
+        String::from_utf8(out).unwrap()
+    };
+    assert_eq!(expanded(&["ns", "expand", "-l", "0", "node[1-3]"]), "");
+    assert_eq!(expanded(&["ns", "expand", "-l", "4", "node[1-3]", "gpu[1-3]"]), "node1 node2 node3\ngpu1\n");
+    assert_eq!(expanded(&["ns", "expand", "-l", "3", "node[1-3]", "gpu[1-3]"]), "node1 node2 node3\n");
+}
+
+#[test]
+fn test_expand_group_digits() {
+    assert_eq!(group_digits("node1000000", '_'), "node1_000_000".to_string());
+    assert_eq!(group_digits("node999 node1000 rack12345-cpu2", ','), "node999 node1,000 rack12,345-cpu2".to_string());
+
+    let args = Arguments::parse_from(["ns", "expand", "node[1000000]"]);
+    let Commands::Expand(expand) = args.command else {
+        panic!("expand subcommand expected")
+    };
+    assert!(!expand.group_digits);
+
+    let args = Arguments::parse_from(["ns", "expand", "-g", "node[1000-1002]"]);
+    let Commands::Expand(expand) = args.command else {
+        panic!("expand subcommand expected")
+    };
+    assert!(expand.group_digits);
+    assert_eq!(expand.group_separator, '_');
+    assert_eq!(expand.nodesets, vec!["node[1000-1002]".to_string()]);
+
+    let args = Arguments::parse_from(["ns", "expand", "-g", "--group-separator", ",", "node[1000-1002]"]);
+    let Commands::Expand(expand) = args.command else {
+        panic!("expand subcommand expected")
+    };
+    assert_eq!(expand.group_separator, ',');
+}
+
+#[test]
+fn test_check() {
+    let args = Arguments::parse_from(["ns", "check", "node[1-4000000000]-cpu[1-4000000000]"]);
+    let Commands::Check(c) = args.command else {
+        panic!("check subcommand expected")
+    };
+    assert_eq!(check(&c, false), 0);
+
+    let args = Arguments::parse_from(["ns", "check", "node[1-10]", "node[1-5"]);
+    let Commands::Check(c) = args.command else {
+        panic!("check subcommand expected")
+    };
+    assert_eq!(check(&c, true), 1);
+
+    // The error that check prints tells where the nodeset is malformed
+    let err = NodeSet::new("node[1-10],gpu[1-5").unwrap_err();
+    assert_eq!(err.to_string(), "unbalanced brackets in '[1-5' at offset 14");
+}
+
+#[test]
+fn test_quiet() {
+    let args = Arguments::parse_from(["ns", "count", "node[1-10]"]);
+    assert!(!args.quiet);
+    let args = Arguments::parse_from(["ns", "-q", "count", "node[1-10]"]);
+    assert!(args.quiet);
+    let args = Arguments::parse_from(["ns", "fold", "--quiet", "node[1-10]"]);
+    assert!(args.quiet);
+
+    let mut out: Vec<u8> = Vec::new();
+    print_error(&mut out, false, NodeSet::new("node[1-").unwrap_err());
+    assert!(String::from_utf8(out).unwrap().starts_with("Error: "));
+
+    let mut out: Vec<u8> = Vec::new();
+    print_error(&mut out, true, NodeSet::new("node[1-").unwrap_err());
+    assert!(out.is_empty());
+}
+
+#[test]
+fn test_contains() {
+    let args = Arguments::parse_from(["ns", "contains", "gpu3", "node[1-10]", "gpu[1-4]"]);
+    let Commands::Contains(c) = args.command else {
+        panic!("contains subcommand expected")
+    };
+    let mut out: Vec<u8> = Vec::new();
+    assert!(contains(&mut out, &c, args.quiet).unwrap());
+    assert_eq!(String::from_utf8(out).unwrap(), "gpu3 is not in node[1-10]\ngpu3 is in gpu[1-4]\n");
+
+    let args = Arguments::parse_from(["ns", "contains", "-q", "gpu5", "node[1-10]", "gpu[1-4]"]);
+    let Commands::Contains(c) = args.command else {
+        panic!("contains subcommand expected")
+    };
+    let mut out: Vec<u8> = Vec::new();
+    assert!(!contains(&mut out, &c, args.quiet).unwrap());
+    assert!(out.is_empty());
+
+    let args = Arguments::parse_from(["ns", "contains", "gpu5", "gpu[1-"]);
+    let Commands::Contains(c) = args.command else {
+        panic!("contains subcommand expected")
+    };
+    assert!(contains(&mut Vec::new(), &c, false).is_err());
+}
+
+#[test]
+fn test_set_operations() {
+    let operands = |nodesets: &[&str]| Operands {
+        nodesets: nodesets.iter().map(|n| n.to_string()).collect(),
+    };
+
+    let o = operands(&["node[1-10],gpu[1-4]", "node[5-20]", "node[1-6]"]);
+    assert_eq!(combine(&o, NodeSet::intersection).unwrap().to_string(), "node[5-6]");
+    assert_eq!(combine(&o, NodeSet::difference).unwrap().to_string(), "gpu[1-4]");
+    assert_eq!(combine(&o, NodeSet::union).unwrap().to_string(), "node[1-20],gpu[1-4]");
+
+    let o = operands(&["node[1-10]", "node[1-3]"]);
+    assert_eq!(combine(&o, NodeSet::difference).unwrap().to_string(), "node[4-10]");
+
+    let o = operands(&["node[1-10]"]);
+    assert_eq!(combine(&o, NodeSet::union).unwrap().to_string(), "node[1-10]");
+
+    let o = operands(&["node[1-10]", "node[1-"]);
+    assert!(combine(&o, NodeSet::union).is_err());
+
+    let args = Arguments::parse_from(["ns", "union", "node[1-2]", "node[3-4]"]);
+    let Commands::Union(o) = args.command else {
+        panic!("union subcommand expected")
+    };
+    assert_eq!(combine(&o, NodeSet::union).unwrap().to_string(), "node[1-4]");
+    assert!(Arguments::try_parse_from(["ns", "intersection"]).is_err());
+}
+
+#[test]
+fn test_fold_format() {
+    let node = || NodeSet::new("node[1-3],gpu1").unwrap();
+    assert_eq!(format_nodeset(node(), Format::Brackets).unwrap(), "node[1-3],gpu1");
+    assert_eq!(format_nodeset(node(), Format::List).unwrap(), "node1,node2,node3,gpu1");
+    assert_eq!(format_nodeset(node(), Format::Json).unwrap(), r#"["node1","node2","node3","gpu1"]"#);
+    assert_eq!(format_nodeset(NodeSet::new("").unwrap(), Format::Json).unwrap(), "[]");
+
+    let json = format_nodeset(NodeSet::new("a\tb\"c\\d[1-2]").unwrap(), Format::Json).unwrap();
+    assert_eq!(json, r#"["a\u0009b\"c\\d1","a\u0009b\"c\\d2"]"#);
+    let names: Vec<String> = serde_json::from_str(&json).unwrap();
+    assert_eq!(names, vec!["a\tb\"c\\d1", "a\tb\"c\\d2"]);
+
+    let args = Arguments::parse_from(["ns", "fold", "node[1-3]"]);
+    let Commands::Fold(f) = args.command else {
+        panic!("fold subcommand expected")
+    };
+    assert_eq!(f.format, Format::Brackets);
+
+    let args = Arguments::parse_from(["ns", "fold", "--format", "json", "node[1-3]"]);
+    let Commands::Fold(f) = args.command else {
+        panic!("fold subcommand expected")
     };
+    assert_eq!(f.format, Format::Json);
 }
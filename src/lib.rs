@@ -33,7 +33,13 @@ mod range;
 /// module to manage a set of range called rangeset such as `1-4,8-14/2,50`
 mod rangeset;
 
-pub use node::{node_to_vec_string, Node};
-pub use nodeset::NodeSet;
-pub use range::{fold_vec_u32_in_vec_range, guess_padding, vec_u32_intersection, Range};
-pub use rangeset::RangeSet;
+/// module implementing serde's Serialize and Deserialize (`serde` feature)
+#[cfg(feature = "serde")]
+mod serde;
+
+pub use node::{node_to_vec_string, ErrorKind, Node, NodeErrorType, ParseOptions};
+pub use nodeset::{FoldStats, NodeSet, NodeSetBuilder, NodeSetConfig};
+pub use range::{fold_values_in_vec_range, fold_values_in_vec_range_max_step, guess_padding, values_intersection, Range, RangeInterner, RangeIter};
+#[allow(deprecated)]
+pub use range::{fold_vec_u32_in_vec_range, fold_vec_u32_in_vec_range_max_step, vec_u32_intersection};
+pub use rangeset::{RangeSet, RangeSetIter};
@@ -23,8 +23,11 @@
 use crate::rangeset::RangeSet;
 use lazy_static::lazy_static;
 use regex::Regex;
+use std::collections::HashSet;
 use std::error::Error;
 use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::io;
 use std::str::FromStr;
 
 #[cfg(test)]
@@ -53,6 +56,11 @@ use std::process::exit;
 /// ```
 /// Note : to transform a node into a vector of Strings you may
 ///        prefer to use `node_to_vec_string()` function.
+///
+/// Node names may contain any UTF-8 character (`nœud[1-4]` is a valid
+/// Node) but only ASCII digits (`0` to `9`) are considered as numbers.
+/// Between brackets single letters may be used instead of numbers:
+/// `rack[A-H]` goes from `rackA` to `rackH`.
 
 /*
  * Structure used to keep Node definition
@@ -69,11 +77,11 @@ use std::process::exit;
 pub struct Node {
     name: String,
     sets: Vec<RangeSet>,
-    values: Vec<(u32, usize)>,
+    values: Vec<(u64, usize)>,
     first: bool,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub enum NodeErrorType {
     Regular(ErrorKind),
 }
@@ -83,6 +91,12 @@ pub enum ErrorKind {
     RegexNoMatch,
     RegexErrorMatch(String),
     RangeSetCreation(String),
+    EmptyRangeSet(usize),
+    IncompatibleNodes(String),
+    TooManyNodes(u64),
+    DuplicateNodes(String),
+    UnbalancedBrackets(String, usize),
+    StepInHostlist(String),
 }
 
 impl ErrorKind {
@@ -91,6 +105,12 @@ impl ErrorKind {
             ErrorKind::RegexNoMatch => "no match found in string",
             ErrorKind::RegexErrorMatch(_) => "matching seems wrong. Verify that ranges are correctly formatted",
             ErrorKind::RangeSetCreation(_) => "unable to create rangeset",
+            ErrorKind::EmptyRangeSet(_) => "empty range in brackets",
+            ErrorKind::IncompatibleNodes(_) => "nodes can not be merged",
+            ErrorKind::TooManyNodes(_) => "too many nodes in nodeset",
+            ErrorKind::DuplicateNodes(_) => "nodes defined more than once",
+            ErrorKind::UnbalancedBrackets(_, _) => "unbalanced brackets",
+            ErrorKind::StepInHostlist(_) => "steps are not allowed in a hostlist",
         }
     }
 }
@@ -102,11 +122,29 @@ impl fmt::Display for NodeErrorType {
                 ErrorKind::RegexNoMatch => write!(f, "{}", err.as_str()),
                 ErrorKind::RegexErrorMatch(s) => write!(f, "{} '{}'", err.as_str(), s),
                 ErrorKind::RangeSetCreation(s) => write!(f, "{} '{}'", err.as_str(), s),
+                ErrorKind::EmptyRangeSet(o) => write!(f, "{} at offset {}", err.as_str(), o),
+                ErrorKind::IncompatibleNodes(s) => write!(f, "{}: {}", err.as_str(), s),
+                ErrorKind::TooManyNodes(n) => write!(f, "{} ({} at most)", err.as_str(), n),
+                ErrorKind::DuplicateNodes(s) => write!(f, "{} '{}'", err.as_str(), s),
+                ErrorKind::UnbalancedBrackets(s, o) => write!(f, "{} in '{}' at offset {}", err.as_str(), s, o),
+                ErrorKind::StepInHostlist(s) => write!(f, "{} '{}'", err.as_str(), s),
             },
         }
     }
 }
 
+impl NodeErrorType {
+    // Moves the offsets of the error by `offset` bytes: used when the
+    // string that was parsed is part of a bigger one (a NodeSet).
+    pub(crate) fn shifted(self, offset: usize) -> Self {
+        match self {
+            NodeErrorType::Regular(ErrorKind::EmptyRangeSet(o)) => NodeErrorType::Regular(ErrorKind::EmptyRangeSet(o + offset)),
+            NodeErrorType::Regular(ErrorKind::UnbalancedBrackets(s, o)) => NodeErrorType::Regular(ErrorKind::UnbalancedBrackets(s, o + offset)),
+            err => err,
+        }
+    }
+}
+
 impl Error for NodeErrorType {
     fn description(&self) -> &str {
         match *self {
@@ -134,43 +172,266 @@ pub fn node_to_vec_string(node_str: &str) -> Result<Vec<String>, Box<dyn Error>>
     Ok(v)
 }
 
+// Checks that each `[` is closed by a `]` before the next `[`. The error
+// names the part of `name` with the unbalanced bracket and its byte
+// offset: `[1-5` (4) when `]` is missing in `node[1-5` and `node1-5]` (0)
+// when `[` is missing in `node1-5]`.
+fn check_brackets(name: &str, opening: char, closing: char) -> Result<(), NodeErrorType> {
+    let mut open: Option<usize> = None;
+    let mut begin = 0;
+
+    for (index, c) in name.char_indices() {
+        match (c, open) {
+            (c, None) if c == opening => open = Some(index),
+            (c, Some(_)) if c == closing => {
+                open = None;
+                begin = index + 1;
+            }
+            (c, Some(first)) if c == opening => return Err(NodeErrorType::Regular(ErrorKind::UnbalancedBrackets(name[first..=index].to_string(), first))),
+            (c, None) if c == closing => return Err(NodeErrorType::Regular(ErrorKind::UnbalancedBrackets(name[begin..=index].to_string(), begin))),
+            _ => (),
+        }
+    }
+
+    match open {
+        Some(first) => Err(NodeErrorType::Regular(ErrorKind::UnbalancedBrackets(name[first..].to_string(), first))),
+        None => Ok(()),
+    }
+}
+
+// Tells whether `hostname` is made of a value of each rangeset each
+// followed by its literal text. A value is a run of digits or a letter:
+// runs of digits are tried from the longest as `rack{}{}` may have two
+// values next to each other (`rack15` for `rack[1-2][5-6]`).
+fn matches_template(sets: &[RangeSet], literals: &[&str], hostname: &str) -> bool {
+    let (set, literal) = match (sets.first(), literals.first()) {
+        (Some(set), Some(literal)) => (set, literal),
+        _ => return hostname.is_empty(),
+    };
+
+    let token_len = if set.is_alpha() {
+        hostname.chars().next().filter(|c| c.is_ascii_alphabetic()).map_or(0, |_| 1)
+    } else {
+        hostname.bytes().take_while(|c| c.is_ascii_digit()).count()
+    };
+
+    (1..=token_len).rev().any(|len| {
+        let (token, rest) = hostname.split_at(len);
+        match rest.strip_prefix(literal) {
+            Some(rest) => set.contains_str(token) && matches_template(&sets[1..], &literals[1..], rest),
+            None => false,
+        }
+    })
+}
+
+// Returns the values of a RangeSet in order without modifying it.
+fn rangeset_values(set: &RangeSet) -> Vec<u64> {
+    let mut set = set.clone();
+    let mut values = Vec::new();
+
+    set.reset();
+    while let Some((value, _)) = set.get_next() {
+        values.push(value);
+    }
+    values
+}
+
 /* This regular expression is used to capture each rangeset in a string defining a Node */
 lazy_static! {
-    pub static ref RE: Regex = Regex::new(r"\[([\d,\-/]+)\]|([\d]+)").unwrap();
+    pub static ref RE: Regex = Regex::new(r"\[([0-9a-zA-Z,\-:/]+)\]|([0-9]+)").unwrap();
+}
+
+/// Characters used to write a node:
+/// * `opening` and `closing` surround rangesets (`[` and `]` by default)
+/// * `separator` separates the ranges of a rangeset (`,` by default)
+///
+/// `node{1-3;8}` is read with `{`, `}` and `;`:
+/// ```rust
+/// use nodeset::{Node, ParseOptions};
+/// let options = ParseOptions { opening: '{', closing: '}', separator: ';' };
+/// let node = Node::new_with_syntax("node{1-3;8}", &options).unwrap();
+/// assert_eq!(node.to_string(), "node[1-3,8]");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseOptions {
+    pub opening: char,
+    pub closing: char,
+    pub separator: char,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions {
+            opening: '[',
+            closing: ']',
+            separator: ',',
+        }
+    }
+}
+
+impl ParseOptions {
+    // Builds the regex that captures rangesets written with these options
+    fn regex(&self) -> Result<Regex, NodeErrorType> {
+        let opening = regex::escape(&self.opening.to_string());
+        let closing = regex::escape(&self.closing.to_string());
+        let separator = regex::escape(&self.separator.to_string());
+
+        match Regex::new(&format!(r"{opening}([0-9a-zA-Z{separator}\-:/]+){closing}|([0-9]+)")) {
+            Ok(re) => Ok(re),
+            Err(e) => Err(NodeErrorType::Regular(ErrorKind::RegexErrorMatch(e.to_string()))),
+        }
+    }
 }
 
 impl Node {
-    /// Counts the number of elements in Node's definition.
-    pub fn len(&self) -> u32 {
+    /// Counts the number of elements in Node's definition. Multi-dimension
+    /// nodes easily have more than u32::MAX nodes: the count is a u64 that
+    /// saturates at u64::MAX instead of overflowing.
+    pub fn len(&self) -> u64 {
         match (self.sets.is_empty(), self.name.is_empty()) {
             (true, true) => 0,
             (true, false) => 1,
-            _ => self.sets.iter().map(|r| r.len()).product(),
+            _ => self.sets.iter().fold(1, |acc: u64, r| acc.saturating_mul(r.len())),
         }
     }
 
+    /// Counts the number of Ranges used by all the rangesets of the Node.
+    pub fn range_count(&self) -> usize {
+        self.sets.iter().map(|r| r.range_count()).sum()
+    }
+
     /// Tells whether a Node is empty or not.
     pub fn is_empty(&self) -> bool {
         self.sets.is_empty() && self.name.is_empty()
     }
 
+    /// Clones the Node with a reset iterator: the clone will
+    /// always iterate from the first node name whatever the
+    /// state of self's iterator is.
+    pub fn fresh_clone(&self) -> Node {
+        let mut sets = self.sets.clone();
+        for set in sets.iter_mut() {
+            set.reset();
+        }
+
+        Node {
+            name: self.name.to_string(),
+            sets,
+            values: vec![(0, 0); self.values.len()],
+            first: true,
+        }
+    }
+
+    /// Returns a new Node that generates the node names of self in
+    /// descending order: `node[1-3]` gives `node[3-1]` that expands to
+    /// `node3,node2,node1`. Each dimension of the node is reversed.
+    pub fn reversed(&self) -> Node {
+        Node::from_sets(&self.name, self.sets.iter().map(|set| set.reversed()).collect())
+    }
+
+    /// Returns a new Node where each rangeset is padded with at least
+    /// `pad` digits: `node[8-10]` with a pad of 3 gives `node[008-010]`.
+    pub(crate) fn with_min_pad(&self, pad: usize) -> Node {
+        Node::from_sets(&self.name, self.sets.iter().map(|set| set.with_min_pad(pad)).collect())
+    }
+
+    /// Returns the name of the Node where each rangeset is replaced by `{}`:
+    /// `rack{}-node{}` for `rack[1-2]-node[1-10]`.
+    pub fn name_template(&self) -> &str {
+        &self.name
+    }
+
+    // True when the rangeset `index` may be displayed without brackets
+    // and still be read back as the same dimension: it must be a single
+    // number (letters are only read between brackets) that is not next
+    // to another rangeset (`n[5][6]` would be read as `n56`).
+    fn displays_inline(&self, index: usize) -> bool {
+        let set = &self.sets[index];
+        let literals: Vec<&str> = self.name.split("{}").collect();
+        let after_set = index > 0 && literals[index].is_empty();
+        let before_set = index + 1 < self.sets.len() && literals[index + 1].is_empty();
+
+        set.is_alone() && !set.is_alpha() && !after_set && !before_set
+    }
+
+    /// Returns the number of rangesets (dimensions) of the Node: 2 for
+    /// `rack[1-2]-node[1-10]` and 0 for `login`.
+    pub fn dimensions(&self) -> usize {
+        self.sets.len()
+    }
+
+    /// Returns the rangeset of the dimension `index` of the Node
+    /// (`1-2` is the dimension 0 of `node[1-2]-cpu[1-4]`).
+    pub(crate) fn dimension(&self, index: usize) -> Option<&RangeSet> {
+        self.sets.get(index)
+    }
+
+    /// True when the Node defines exactly one node name
+    /// such as `node5` or `node[5]`.
+    pub fn is_single(&self) -> bool {
+        !self.is_empty() && self.sets.iter().all(|r| r.len() == 1)
+    }
+
+    /// Displays the Node as a hostlist (no step and values in ascending
+    /// order): `node[1-7/2]` gives `node[1,3,5,7]`.
+    pub fn to_hostlist(&self) -> String {
+        let mut nodestr: &str = self.name.as_str();
+        let mut replaced;
+        for (index, set) in self.sets.iter().enumerate() {
+            if set.len() == 1 && self.displays_inline(index) {
+                replaced = nodestr.replacen("{}", set.to_hostlist().as_str(), 1)
+            } else {
+                replaced = nodestr.replacen("{}", format!("[{}]", set.to_hostlist()).as_str(), 1)
+            };
+            nodestr = replaced.as_str();
+        }
+        nodestr.to_string()
+    }
+
+    /// Writes the node names of the Node separated by `sep` into `w`
+    /// as they are generated: no name is kept in memory.
+    pub fn expand_to_writer<W: io::Write>(&self, sep: &str, w: &mut W) -> io::Result<()> {
+        for (index, name) in self.fresh_clone().enumerate() {
+            if index > 0 {
+                w.write_all(sep.as_bytes())?;
+            }
+            w.write_all(name.as_bytes())?;
+        }
+        Ok(())
+    }
+
     /// Transforms a nodeset (String) into a string by expanding the created Node structure.
+    /// Returns an error if the number of nodes can not be represented on this platform.
     pub fn expand<S: AsRef<str>>(&self, separator: S) -> Result<String, Box<dyn Error>> {
+        let count = self.sets.iter().try_fold(1_u64, |acc, r| acc.checked_mul(r.len()));
+        if count.and_then(|c| usize::try_from(c).ok()).is_none() {
+            return Err(format!("Too many nodes in {self} to expand them").into());
+        }
+
+        // Iterates over a fresh clone of self: parsing the Display of self
+        // again could give another Node (padding is normalized for instance)
         #[rustfmt::skip]
-        let out = Node::new(&self.to_string())?
-            .into_iter()
+        let out = self.fresh_clone()
             .collect::<Vec<String>>()
             .join(separator.as_ref());
 
         Ok(out)
     }
 
+    /// Transforms a Node into a string by expanding it exactly as `expand()`
+    /// does. The text between the dimensions of the node (such as `-` in
+    /// `node[1-2]-cpu[1-2]` or `.` in `rack[1-2].node[1-2]`) is kept as is
+    /// in each node name and `separator` is only used between node names.
+    pub fn expand_structured(&self, separator: &str) -> Result<String, Box<dyn Error>> {
+        self.expand(separator)
+    }
+
     /// Intersection of self Node with an other Node :
     ///  `node[1,3-5,89]-cpu[2-4]` and `node[9-2,89,101,2-8/2]-cpu[1-3]`
     ///  -> `node[3-5,89]-cpu[2-3]`
     pub fn intersection(&self, other: &Self) -> Option<Node> {
         let mut ns_sets: Vec<RangeSet> = Vec::new();
-        let mut values: Vec<(u32, usize)> = Vec::new();
+        let mut values: Vec<(u64, usize)> = Vec::new();
 
         if self.name != other.name {
             None
@@ -193,27 +454,166 @@ impl Node {
         }
     }
 
-    /// Union of Node with an other Node
-    pub fn union(&self, other: &Self) -> Result<Self, Box<dyn Error>> {
-        let mut ns_sets: Vec<RangeSet> = Vec::new();
-        let mut values: Vec<(u32, usize)> = Vec::new();
+    /// Returns the node name at position `index` in iteration order (the
+    /// first name being at position 0) without iterating: the position is
+    /// decomposed over the lengths of the rangesets, the last one varying
+    /// the fastest. The name 4 of `node[1-3]-cpu[1-2]` is `node3-cpu1`.
+    pub fn nth_name(&self, index: u64) -> Option<String> {
+        let mut index = index;
+        let mut values = vec![String::new(); self.sets.len()];
 
+        for (value, set) in values.iter_mut().zip(&self.sets).rev() {
+            let len = set.len();
+            if len == 0 {
+                return None;
+            }
+            *value = set.nth_str(index % len)?;
+            index /= len;
+        }
+
+        if index != 0 {
+            return None;
+        }
+
+        let mut name = self.name.to_string();
+        for value in values {
+            name = name.replacen("{}", &value, 1);
+        }
+        Some(name)
+    }
+
+    /// Tells whether `hostname` is one of the node names of the Node:
+    /// `node[1-10]-cpu[1-2]` contains `node4-cpu2` but not `node04-cpu2`.
+    /// No node name is generated.
+    pub fn contains(&self, hostname: &str) -> bool {
+        let literals: Vec<&str> = self.name.split("{}").collect();
+
+        match hostname.strip_prefix(literals[0]) {
+            Some(rest) if literals.len() == self.sets.len() + 1 => matches_template(&self.sets, &literals[1..], rest),
+            _ => false,
+        }
+    }
+
+    /// Returns the coordinates (one value per rangeset) of the nodes of
+    /// self that are not nodes of other. `a[1-2]-b[1-2]` minus `a[1]-b[1]`
+    /// gives `[[1, 2], [2, 1], [2, 2]]`. When the templates of the two Nodes
+    /// are not the same all coordinates of self are returned.
+    pub fn coordinate_difference(&self, other: &Node) -> Vec<Vec<u64>> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+
+        let other_values: Option<Vec<HashSet<u64>>> = if self.name == other.name && !other.is_empty() {
+            Some(other.sets.iter().map(|set| rangeset_values(set).into_iter().collect()).collect())
+        } else {
+            None
+        };
+
+        let mut coordinates: Vec<Vec<u64>> = vec![Vec::new()];
+        for set in &self.sets {
+            let values = rangeset_values(set);
+            coordinates = coordinates.iter().flat_map(|c| values.iter().map(move |v| [c.as_slice(), &[*v]].concat())).collect();
+        }
+
+        #[rustfmt::skip]
+        let difference = coordinates
+            .into_iter()
+            .filter(|c| match &other_values {
+                Some(other_values) => c.iter().zip(other_values).any(|(v, values)| !values.contains(v)),
+                None => true,
+            })
+            .collect();
+
+        difference
+    }
+
+    /// Difference of self Node with an other Node: `node[1-10]` minus
+    /// `node[3-5]` gives `node[1-2,6-10]`. None is returned when the names
+    /// differ or when no node is left.
+    ///
+    /// For multi-dimension nodes the difference is only a Node when all
+    /// dimensions but one are the same in both Nodes (or when the Nodes
+    /// have no node in common): `a[1-2]-b[1-2]` minus `a1-b1` is made of
+    /// `a2-b[1-2]` and `a1-b2` that can not be written as one Node and
+    /// None is returned. `NodeSet::new_excluding()` handles these cases.
+    pub fn difference(&self, other: &Self) -> Option<Node> {
         if self.name != other.name {
-            return Err("Cannot calculate the union of different node ranges !".into());
+            return None;
         }
 
-        for (i, rs_a) in self.sets.iter().enumerate() {
-            let rs_b: &RangeSet = &other.sets[i];
-            ns_sets.push(rs_a.union(rs_b));
-            values.push((0, 0));
+        let mut parts = self.difference_parts(other);
+        match parts.len() {
+            1 => parts.pop(),
+            _ => None,
         }
+    }
 
-        Ok(Node {
-            name: self.name.to_string(),
-            sets: ns_sets,
-            values,
-            first: false,
-        })
+    /// Returns Nodes that have no node in common and that define, all
+    /// together, the nodes of self that are not nodes of other:
+    /// `a[1-2]-b[1-2]` minus `a1-b1` gives `a2-b[1-2]` and `a1-b2`.
+    /// When the names are not the same self is returned as is.
+    pub(crate) fn difference_parts(&self, other: &Self) -> Vec<Node> {
+        if self.name != other.name {
+            return vec![self.fresh_clone()];
+        }
+
+        let mut parts: Vec<Node> = Vec::new();
+        let mut common: Vec<RangeSet> = Vec::new();
+        for (i, (rs_a, rs_b)) in self.sets.iter().zip(&other.sets).enumerate() {
+            /* nodes in common with other for the previous rangesets but not for this one */
+            if let Some(remaining) = rs_a.difference(rs_b) {
+                let mut sets = common.clone();
+                sets.push(remaining);
+                sets.extend(self.sets[i + 1..].iter().cloned());
+                parts.push(Node::from_sets(&self.name, sets));
+            }
+            match rs_a.intersection(rs_b) {
+                Some(inter) => common.push(inter),
+                None => break,
+            }
+        }
+        parts
+    }
+
+    // Creates a Node from its name template and its rangesets
+    fn from_sets(name: &str, sets: Vec<RangeSet>) -> Node {
+        Node {
+            name: name.to_string(),
+            values: vec![(0, 0); sets.len()],
+            sets,
+            first: true,
+        }
+        .fresh_clone()
+    }
+
+    /// Union of Node with an other Node: `node[1-5]` and `node[4-10]`
+    /// gives `node[1-10]`. Both Nodes must have the same name.
+    ///
+    /// Multi-dimension Nodes can only be merged into one Node when all
+    /// their dimensions but one have the same values: `rack[1-2]-node[1-5]`
+    /// and `rack[1-2]-node[6-8]` gives `rack[1-2]-node[1-8]` whereas
+    /// `rack1-node1` and `rack2-node2` can not be merged (`rack[1-2]-node[1-2]`
+    /// would also define `rack1-node2` and `rack2-node1`) and an error is
+    /// returned.
+    pub fn union(&self, other: &Self) -> Result<Self, NodeErrorType> {
+        if self.name != other.name || self.sets.len() != other.sets.len() {
+            return Err(NodeErrorType::Regular(ErrorKind::IncompatibleNodes(format!("{self} and {other} have different names"))));
+        }
+        if self.sets.iter().zip(&other.sets).any(|(a, b)| a.is_alpha() != b.is_alpha()) {
+            return Err(NodeErrorType::Regular(ErrorKind::IncompatibleNodes(format!("{self} and {other} mix letters and numbers"))));
+        }
+
+        let different: Vec<usize> = (0..self.sets.len()).filter(|&i| !self.sets[i].same_values(&other.sets[i])).collect();
+        if different.len() > 1 {
+            return Err(NodeErrorType::Regular(ErrorKind::IncompatibleNodes(format!("{self} and {other} differ on more than one dimension"))));
+        }
+
+        let mut sets = self.sets.clone();
+        for i in different {
+            sets[i] = self.sets[i].union(&other.sets[i]);
+        }
+
+        Ok(Node::from_sets(&self.name, sets))
     }
 
     /* Captures with regex all possible (and non overlapping) rangeset in the node name
@@ -221,11 +621,33 @@ impl Node {
      * It will capture mixed types of rangesets ie: rack1-node[1-42]-cpu2
      */
     pub fn capture_with_regex<S: AsRef<str>>(nodename: S) -> Result<(String, Vec<String>), NodeErrorType> {
+        Node::capture_with_syntax(nodename, &ParseOptions::default())
+    }
+
+    /// Same as `capture_with_regex()` for a node written with `options`.
+    /// The rangesets are returned with `,` as separator.
+    pub fn capture_with_syntax<S: AsRef<str>>(nodename: S, options: &ParseOptions) -> Result<(String, Vec<String>), NodeErrorType> {
+        let custom;
+        let re: &Regex = if *options == ParseOptions::default() {
+            &RE
+        } else {
+            custom = options.regex()?;
+            &custom
+        };
+
         let mut rangesets: Vec<String> = Vec::new();
         let mut name = nodename.as_ref().to_string();
-        for capture in RE.captures_iter(nodename.as_ref()) {
+        if let Some(offset) = name.find(&format!("{}{}", options.opening, options.closing)) {
+            return Err(NodeErrorType::Regular(ErrorKind::EmptyRangeSet(offset)));
+        }
+        check_brackets(&name, options.opening, options.closing)?;
+        for capture in re.captures_iter(nodename.as_ref()) {
             match capture.get(1) {
-                Some(text) => rangesets.push(text.as_str().to_string()),
+                // Brackets with empty segments only (`node[,]`)
+                Some(text) if text.as_str().split(options.separator).all(str::is_empty) => {
+                    return Err(NodeErrorType::Regular(ErrorKind::EmptyRangeSet(text.start() - options.opening.len_utf8())));
+                }
+                Some(text) => rangesets.push(text.as_str().replace(options.separator, ",")),
                 None => {
                     if let Some(text) = capture.get(2) {
                         rangesets.push(text.as_str().to_string())
@@ -234,10 +656,13 @@ impl Node {
             };
         }
         if !rangesets.is_empty() {
-            name = RE.replace_all(nodename.as_ref(), "{}").to_string();
+            name = re.replace_all(nodename.as_ref(), "{}").to_string();
         }
         // name that still contains these characters indicates that the nodename is malformed.
-        if name.contains('[') || name.contains(']') || name.contains('/') {
+        // They are looked for once the rangesets are removed as `{}` may be used as brackets.
+        // `[` and `]` are always refused as the Node is displayed with them.
+        let leftover = re.replace_all(nodename.as_ref(), "");
+        if leftover.contains([options.opening, options.closing, '[', ']', '/']) {
             return Err(NodeErrorType::Regular(ErrorKind::RegexErrorMatch(name)));
         }
 
@@ -246,9 +671,15 @@ impl Node {
 
     /// Node examples: "node[1-5/2]" or "rack[1,3-5,89]" or "cpu[1-2]core[1-64]" or "node01"
     pub fn new<S: AsRef<str>>(str: S) -> Result<Node, NodeErrorType> {
-        let (name, rangesets) = Node::capture_with_regex(str)?;
+        Node::new_with_syntax(str, &ParseOptions::default())
+    }
+
+    /// Creates a Node written with `options` such as `node{1-3}` or
+    /// `node(1-3;8)` (see ParseOptions).
+    pub fn new_with_syntax<S: AsRef<str>>(str: S, options: &ParseOptions) -> Result<Node, NodeErrorType> {
+        let (name, rangesets) = Node::capture_with_syntax(str, options)?;
         let mut sets: Vec<RangeSet> = Vec::new();
-        let mut values: Vec<(u32, usize)> = Vec::new();
+        let mut values: Vec<(u64, usize)> = Vec::new();
         for set in rangesets {
             let rangeset = match RangeSet::new(&set) {
                 Ok(r) => r,
@@ -272,14 +703,14 @@ impl Node {
 
         for i in 0..self.sets.len() {
             let (current, pad) = self.values[i];
-            replaced = nodestr.replacen("{}", format!("{current:0pad$}").as_str(), 1);
+            replaced = nodestr.replacen("{}", self.sets[i].format_value(current, pad).as_str(), 1);
             nodestr = replaced.as_str();
         }
 
         nodestr.to_string()
     }
 
-    fn get_next(&mut self) -> Option<(u32, usize)> {
+    fn get_next(&mut self) -> Option<(u64, usize)> {
         for i in (0..self.sets.len()).rev() {
             //println!("{}: {:?}", i, self.sets[i]);
             match self.sets[i].get_next() {
@@ -361,13 +792,24 @@ impl PartialEq for Node {
     }
 }
 
+impl Eq for Node {}
+
+/// Hash trait for Node consistent with equality: the name and the
+/// rangesets are hashed, padding and iterator's state are not.
+impl Hash for Node {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+        self.sets.hash(state);
+    }
+}
+
 /// Display trait for Node. It will display the node in a folded way (node[1-9/2,98])
 impl fmt::Display for Node {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let mut nodestr: &str = self.name.as_str();
         let mut replaced;
-        for set in &self.sets {
-            if set.is_alone() {
+        for (index, set) in self.sets.iter().enumerate() {
+            if self.displays_inline(index) {
                 replaced = nodestr.replacen("{}", format!("{set}").as_str(), 1)
             } else {
                 replaced = nodestr.replacen("{}", format!("[{set}]").as_str(), 1)
@@ -467,6 +909,12 @@ fn testing_nodes_values() {
     let value = get_node_values_from_str("rack1-node[1-3]-cpu2");
     assert_eq!(value, vec!["rack1-node1-cpu2", "rack1-node2-cpu2", "rack1-node3-cpu2"]);
 
+    let value = get_node_values_from_str("rack[5000000000-5000000001]");
+    assert_eq!(value, vec!["rack5000000000", "rack5000000001"]);
+
+    let value = get_node_values_from_str("node[1-3000/1k]");
+    assert_eq!(value, vec!["node1", "node1001", "node2001"]);
+
     let value = get_node_values_from_str("rack[1-2]-node[1-2]-cpu[1-2]");
     assert_eq!(value, vec!["rack1-node1-cpu1", "rack1-node1-cpu2", "rack1-node2-cpu1", "rack1-node2-cpu2", "rack2-node1-cpu1", "rack2-node1-cpu2", "rack2-node2-cpu1", "rack2-node2-cpu2"]);
 }
@@ -499,3 +947,386 @@ fn testing_node_intersection() {
     println!("{inter:?}");
     assert_eq!(inter, None);
 }
+
+#[test]
+fn testing_node_is_single() {
+    let node: Node = "node5".parse().unwrap();
+    assert!(node.is_single());
+
+    let node: Node = "node[5]".parse().unwrap();
+    assert!(node.is_single());
+
+    let node: Node = "toto".parse().unwrap();
+    assert!(node.is_single());
+
+    let node: Node = "rack[2]-node[5]".parse().unwrap();
+    assert!(node.is_single());
+
+    let node: Node = "node[1-2]".parse().unwrap();
+    assert!(!node.is_single());
+
+    let node: Node = "rack[2]-node[5,7]".parse().unwrap();
+    assert!(!node.is_single());
+}
+
+#[test]
+fn testing_node_non_ascii() {
+    let value = get_node_values_from_str("nœud[1-3]");
+    assert_eq!(value, vec!["nœud1", "nœud2", "nœud3"]);
+
+    let value = get_node_values_from_str("ñode[08-10]-çpu2");
+    assert_eq!(value, vec!["ñode08-çpu2", "ñode09-çpu2", "ñode10-çpu2"]);
+
+    // Non ASCII digits are not numbers: the name is taken literally
+    let node: Node = "node٣".parse().unwrap();
+    assert_eq!(node.len(), 1);
+    assert_eq!(node.to_string(), "node٣".to_string());
+
+    assert!(Node::new("node[٣-٥]").is_err());
+}
+
+#[test]
+fn testing_node_fresh_clone() {
+    let mut node: Node = "rack[1-2]-node[1-2]".parse().unwrap();
+    node.next();
+    node.next();
+    node.next();
+    let clone = node.fresh_clone();
+    assert_eq!(node.collect::<Vec<String>>(), vec!["rack2-node2"]);
+    assert_eq!(clone.collect::<Vec<String>>(), vec!["rack1-node1", "rack1-node2", "rack2-node1", "rack2-node2"]);
+
+    // Nodes resulting from an intersection can be iterated from a fresh clone
+    let node_a: Node = "node[1-10]-cpu[1-4]".parse().unwrap();
+    let node_b: Node = "node[2-3]-cpu[2]".parse().unwrap();
+    let inter = node_a.intersection(&node_b).unwrap();
+    assert_eq!(inter.fresh_clone().collect::<Vec<String>>(), vec!["node2-cpu2", "node3-cpu2"]);
+}
+
+#[test]
+fn testing_node_expand_too_big() {
+    let node: Node = "node[1-1000]-cpu[1-100]".parse().unwrap();
+    let expanded = node.expand(",").unwrap();
+    assert_eq!(expanded.split(',').count(), 100000);
+    assert!(expanded.starts_with("node1-cpu1,node1-cpu2,"));
+
+    let node: Node = "n[0-4000000000]-c[0-4000000000]-d[0-4000000000]".parse().unwrap();
+    assert!(node.expand(",").is_err());
+}
+
+#[test]
+fn testing_node_expand_separators() {
+    let node: Node = "rack[1-2].node[1-2]".parse().unwrap();
+    assert_eq!(node.expand(",").unwrap(), "rack1.node1,rack1.node2,rack2.node1,rack2.node2");
+
+    let node: Node = "node[1-2]-cpu[3-4]".parse().unwrap();
+    assert_eq!(node.expand(" ").unwrap(), "node1-cpu3 node1-cpu4 node2-cpu3 node2-cpu4");
+    assert_eq!(node.expand_structured(" ").unwrap(), node.expand(" ").unwrap());
+
+    let node: Node = "n[1-2][5-6]".parse().unwrap();
+    assert_eq!(node.expand(",").unwrap(), "n15,n16,n25,n26");
+
+    let node: Node = "x[1-2]::y[01-02]_z3".parse().unwrap();
+    assert_eq!(node.expand_structured(";").unwrap(), "x1::y01_z3;x1::y02_z3;x2::y01_z3;x2::y02_z3");
+}
+
+#[test]
+fn testing_node_empty_brackets() {
+    let err = Node::new("node[]").unwrap_err();
+    assert!(matches!(err, NodeErrorType::Regular(ErrorKind::EmptyRangeSet(4))));
+    assert_eq!(err.to_string(), "empty range in brackets at offset 4");
+
+    assert_eq!(Node::new("rack[1-2]node[]").unwrap_err(), NodeErrorType::Regular(ErrorKind::EmptyRangeSet(13)));
+
+    // Brackets with empty segments only are empty too
+    let err = Node::new("node[,]").unwrap_err();
+    assert!(matches!(err, NodeErrorType::Regular(ErrorKind::EmptyRangeSet(4))));
+    let err = Node::new("rack[1-2]-node[,,]").unwrap_err();
+    assert!(matches!(err, NodeErrorType::Regular(ErrorKind::EmptyRangeSet(14))));
+}
+
+#[test]
+fn testing_node_coordinate_difference() {
+    let node_a: Node = "a[1-2]-b[1-2]".parse().unwrap();
+    let node_b: Node = "a[1]-b[1]".parse().unwrap();
+    assert_eq!(node_a.coordinate_difference(&node_b), vec![vec![1, 2], vec![2, 1], vec![2, 2]]);
+    assert!(node_b.coordinate_difference(&node_a).is_empty());
+
+    let node_c: Node = "c[1]-b[1]".parse().unwrap();
+    assert_eq!(node_a.coordinate_difference(&node_c).len(), 4);
+
+    let login: Node = "login".parse().unwrap();
+    assert!(login.coordinate_difference(&login.clone()).is_empty());
+    assert_eq!(login.coordinate_difference(&node_a), vec![Vec::<u64>::new()]);
+}
+
+#[test]
+fn testing_node_reversed() {
+    let node: Node = "node[1-3]".parse().unwrap();
+    assert_eq!(node.reversed().collect::<Vec<String>>(), vec!["node3", "node2", "node1"]);
+
+    let node: Node = "rack[1-2]-node[01-05/2,7]".parse().unwrap();
+    let reversed = node.reversed();
+    assert_eq!(reversed.to_string(), "rack[2-1]-node[7,05-01/2]");
+    let mut names = node.collect::<Vec<String>>();
+    names.reverse();
+    assert_eq!(reversed.collect::<Vec<String>>(), names);
+}
+
+#[test]
+fn testing_node_len_above_u32() {
+    let node: Node = "node[0-99999]-cpu[0-99999]".parse().unwrap();
+    assert_eq!(node.len(), 10_000_000_000_u64);
+    assert!(node.len() > u32::MAX as u64);
+
+    let node: Node = "n[0-4000000000]-c[0-4000000000]-d[0-4000000000]".parse().unwrap();
+    assert_eq!(node.len(), u64::MAX);
+}
+
+#[test]
+fn testing_node_colon_separator() {
+    let node: Node = "node[1:3,8]".parse().unwrap();
+    assert_eq!(node.to_string(), "node[1-3,8]");
+}
+
+#[test]
+fn testing_node_letters() {
+    let value = get_node_values_from_str("node[a-c]");
+    assert_eq!(value, vec!["nodea", "nodeb", "nodec"]);
+
+    let value = get_node_values_from_str("rack[C-A]-node[1-2]");
+    assert_eq!(value, vec!["rackC-node1", "rackC-node2", "rackB-node1", "rackB-node2", "rackA-node1", "rackA-node2"]);
+
+    let node: Node = "rack[A-H/2]".parse().unwrap();
+    assert_eq!(node.to_string(), "rack[A-H/2]");
+    assert_eq!(node.len(), 4);
+
+    let node_a: Node = "rack[a-c]".parse().unwrap();
+    let node_b: Node = "rack[d-f]".parse().unwrap();
+    assert_eq!(node_a.union(&node_b).unwrap().to_string(), "rack[a-f]");
+    let node_c: Node = "rack[1-3]".parse().unwrap();
+    assert!(node_a.union(&node_c).is_err());
+
+    assert!(Node::new("node[a-c,1]").is_err());
+    assert!(Node::new("node[a-3]").is_err());
+}
+
+#[test]
+fn testing_node_difference() {
+    let node_a: Node = "node[1-10]".parse().unwrap();
+    let node_b: Node = "node[3-5]".parse().unwrap();
+    assert_eq!(node_a.difference(&node_b).unwrap().to_string(), "node[1-2,6-10]");
+    assert!(node_b.difference(&node_a).is_none());
+
+    let node_c: Node = "gpu[3-5]".parse().unwrap();
+    assert!(node_a.difference(&node_c).is_none());
+
+    let node_a: Node = "rack[1-2]-node[1-10]".parse().unwrap();
+    let node_b: Node = "rack[1-2]-node[5-20]".parse().unwrap();
+    assert_eq!(node_a.difference(&node_b).unwrap().to_string(), "rack[1-2]-node[1-4]");
+
+    let node_b: Node = "rack[3-4]-node[5-20]".parse().unwrap();
+    assert_eq!(node_a.difference(&node_b).unwrap(), node_a);
+
+    // a2-b[1-2] and a1-b2 can not be written as one Node
+    let node_a: Node = "a[1-2]-b[1-2]".parse().unwrap();
+    let node_b: Node = "a1-b1".parse().unwrap();
+    assert!(node_a.difference(&node_b).is_none());
+}
+
+#[test]
+fn testing_node_union() {
+    let node_a: Node = "node[1-10]".parse().unwrap();
+    let node_b: Node = "node[5-20]".parse().unwrap();
+    assert_eq!(node_a.union(&node_b).unwrap(), Node::new("node[1-20]").unwrap());
+
+    let node_c: Node = "gpu-node[1-20/2]".parse().unwrap();
+    assert!(node_a.union(&node_c).is_err());
+
+    let node_a: Node = "rack[1-2]-node[1-5]".parse().unwrap();
+    let node_b: Node = "rack[2,1]-node[6-8]".parse().unwrap();
+    assert_eq!(node_a.union(&node_b).unwrap().to_string(), "rack[1-2]-node[1-8]");
+
+    let node_a: Node = "rack1-node1".parse().unwrap();
+    let node_b: Node = "rack2-node2".parse().unwrap();
+    let err = node_a.union(&node_b).unwrap_err();
+    assert!(matches!(err, NodeErrorType::Regular(ErrorKind::IncompatibleNodes(_))));
+
+    let login: Node = "login".parse().unwrap();
+    assert_eq!(login.union(&login.clone()).unwrap().to_string(), "login");
+}
+
+#[test]
+fn testing_node_len_without_iterating() {
+    // Iterating over these nodes would take minutes
+    let node: Node = "node[1-1000000000]".parse().unwrap();
+    assert_eq!(node.len(), 1_000_000_000);
+    let node: Node = "rack[1-1000000]-node[1-1000000000]-cpu[1-64]".parse().unwrap();
+    assert_eq!(node.len(), 64_000_000_000_000_000);
+    let range: crate::Range = "1-1000000000".parse().unwrap();
+    assert_eq!(range.count(), 1_000_000_000);
+}
+
+#[test]
+fn testing_node_contains() {
+    let node: Node = "node[1-10]-cpu[1-2]".parse().unwrap();
+    assert!(node.contains("node4-cpu2"));
+    assert!(node.contains("node10-cpu1"));
+    assert!(!node.contains("node04-cpu2"));
+    assert!(!node.contains("node11-cpu1"));
+    assert!(!node.contains("node4-cpu2 "));
+    assert!(!node.contains("gpu4-cpu2"));
+
+    let node: Node = "rack[1-2][5-6]".parse().unwrap();
+    assert!(node.contains("rack15"));
+    assert!(!node.contains("rack17"));
+
+    let node: Node = "rack[A-C]-n[08-10]".parse().unwrap();
+    assert!(node.contains("rackB-n09"));
+    assert!(!node.contains("rackD-n09"));
+
+    let login: Node = "login".parse().unwrap();
+    assert!(login.contains("login"));
+    assert!(!login.contains("login1"));
+}
+
+#[test]
+fn testing_node_nth_name() {
+    let node: Node = "rack[1-2]-node[01-05/2]-cpu[7-8,10]".parse().unwrap();
+    let names: Vec<String> = node.fresh_clone().collect();
+    assert_eq!(names.len(), 18);
+
+    for (index, name) in names.iter().enumerate() {
+        assert_eq!(node.nth_name(index as u64).as_ref(), Some(name));
+    }
+    assert_eq!(node.nth_name(18), None);
+
+    let login: Node = "login".parse().unwrap();
+    assert_eq!(login.nth_name(0), Some("login".to_string()));
+    assert_eq!(login.nth_name(1), None);
+}
+
+#[test]
+fn testing_node_hash() {
+    let mut nodes = HashSet::new();
+    nodes.insert(Node::new("node[1-10]").unwrap());
+    nodes.insert(Node::new("node[01-10]").unwrap());
+    assert_eq!(nodes.len(), 1);
+
+    nodes.insert(Node::new("gpu[1-10]").unwrap());
+    nodes.insert(Node::new("node[1-10]-cpu1").unwrap());
+    assert_eq!(nodes.len(), 3);
+}
+
+#[test]
+fn testing_node_expand_to_writer() {
+    let node = Node::new("rack[1-2]-node[01-03]").unwrap();
+    let mut out: Vec<u8> = Vec::new();
+    node.expand_to_writer(" ", &mut out).unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), node.expand(" ").unwrap());
+
+    let login = Node::new("login").unwrap();
+    let mut out: Vec<u8> = Vec::new();
+    login.expand_to_writer(",", &mut out).unwrap();
+    assert_eq!(out, b"login");
+}
+
+#[test]
+fn testing_node_expand_without_reparse() {
+    let node = Node::new("rack[01-02,8]-node[001-003/2,10]").unwrap();
+    let collected: Vec<String> = node.fresh_clone().collect();
+    assert_eq!(node.expand(",").unwrap(), collected.join(","));
+    assert_eq!(collected[0], "rack01-node001");
+    assert_eq!(collected[collected.len() - 1], "rack8-node10");
+
+    // Partly iterated nodes are expanded from their first node name
+    let mut node = node;
+    node.next();
+    node.next();
+    assert_eq!(node.expand(",").unwrap(), collected.join(","));
+}
+
+#[test]
+fn testing_node_unbalanced_brackets() {
+    let err = Node::new("node[1-5").unwrap_err();
+    assert!(matches!(&err, NodeErrorType::Regular(ErrorKind::UnbalancedBrackets(s, 4)) if s == "[1-5"));
+    assert_eq!(err.to_string(), "unbalanced brackets in '[1-5' at offset 4");
+
+    let err = Node::new("rack[1-2]-node1-5]").unwrap_err();
+    assert!(matches!(&err, NodeErrorType::Regular(ErrorKind::UnbalancedBrackets(s, 9)) if s == "-node1-5]"));
+
+    let err = Node::new("node[1-5[").unwrap_err();
+    assert!(matches!(&err, NodeErrorType::Regular(ErrorKind::UnbalancedBrackets(s, 4)) if s == "[1-5["));
+
+    assert!(Node::new("rack[1-2]-node[1-5]").is_ok());
+}
+
+#[test]
+fn testing_node_error_equality() {
+    assert_eq!(Node::new("node[]").unwrap_err(), NodeErrorType::Regular(ErrorKind::EmptyRangeSet(4)));
+    assert_eq!(Node::new("node[1-5").unwrap_err(), NodeErrorType::Regular(ErrorKind::UnbalancedBrackets("[1-5".to_string(), 4)));
+    assert_ne!(Node::new("node[1-5").unwrap_err(), NodeErrorType::Regular(ErrorKind::EmptyRangeSet(4)));
+
+    let err = Node::new("node[1-x]").unwrap_err();
+    assert_eq!(err.clone(), err);
+}
+
+#[test]
+fn testing_node_dimensions_and_template() {
+    let node: Node = "node[1-10]".parse().unwrap();
+    assert_eq!(node.dimensions(), 1);
+    assert_eq!(node.name_template(), "node{}");
+
+    let node: Node = "rack[1-2]-node[01-10]-cpu4".parse().unwrap();
+    assert_eq!(node.dimensions(), 3);
+    assert_eq!(node.name_template(), "rack{}-node{}-cpu{}");
+
+    let node: Node = "login".parse().unwrap();
+    assert_eq!(node.dimensions(), 0);
+    assert_eq!(node.name_template(), "login");
+}
+
+#[test]
+fn testing_node_new_with_syntax() {
+    let curly = ParseOptions {
+        opening: '{',
+        closing: '}',
+        ..ParseOptions::default()
+    };
+    let node = Node::new_with_syntax("node{1-3}", &curly).unwrap();
+    assert_eq!(node, Node::new("node[1-3]").unwrap());
+    assert_eq!(node.expand(" ").unwrap(), Node::new("node[1-3]").unwrap().expand(" ").unwrap());
+
+    let parenthesis = ParseOptions {
+        opening: '(',
+        closing: ')',
+        separator: ';',
+    };
+    let node = Node::new_with_syntax("rack(1;4)-node(01-10/3)", &parenthesis).unwrap();
+    assert_eq!(node.to_string(), "rack[1,4]-node[01-10/3]");
+
+    assert_eq!(Node::new_with_syntax("node{}", &curly).unwrap_err(), NodeErrorType::Regular(ErrorKind::EmptyRangeSet(4)));
+    assert_eq!(Node::new_with_syntax("node{1-3", &curly).unwrap_err(), NodeErrorType::Regular(ErrorKind::UnbalancedBrackets("{1-3".to_string(), 4)));
+    assert!(Node::new_with_syntax("node[1-3]", &curly).is_err());
+}
+
+#[test]
+fn testing_node_display_round_trip() {
+    let node_a = Node::new("node[1-10]-cpu[1-4]").unwrap();
+    let node_b = Node::new("node[1-10]-cpu[2]").unwrap();
+    let inter = node_a.intersection(&node_b).unwrap();
+    assert_eq!(inter.to_string(), "node[1-10]-cpu2");
+    assert_eq!(Node::new(inter.to_string()).unwrap(), inter);
+
+    let node_a = Node::new("rack[A-C]-node[1-10]").unwrap();
+    let node_b = Node::new("rack[B]-node[5-20]").unwrap();
+    let inter = node_a.intersection(&node_b).unwrap();
+    assert_eq!(inter.to_string(), "rack[B]-node[5-10]");
+    assert_eq!(Node::new(inter.to_string()).unwrap(), inter);
+
+    let node_a = Node::new("n[1-2][5-6]").unwrap();
+    let node_b = Node::new("n[1][6]").unwrap();
+    let inter = node_a.intersection(&node_b).unwrap();
+    assert_eq!(inter.to_string(), "n[1][6]");
+    assert_eq!(Node::new(inter.to_string()).unwrap(), inter);
+    assert_eq!(inter.dimensions(), 2);
+}
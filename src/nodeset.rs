@@ -20,21 +20,133 @@
  *  Inc., 59 Temple Place - Suite 330, Boston, MA 02111-1307, USA.
  */
 
-use crate::node::{Node, NodeErrorType};
+use crate::node::{ErrorKind, Node, NodeErrorType, ParseOptions};
+use std::collections::BTreeSet;
 use std::error::Error;
 use std::fmt;
+use std::io;
+use std::ops::{BitAnd, BitOr, Sub};
 use std::str::FromStr;
 
 #[derive(Debug)]
 pub struct NodeSet {
     set: Vec<Node>,
     current_iter_index: Option<usize>,
+    cache: Option<(String, String)>,
+}
+
+/* Part of a node name used to sort node names in natural order: numbers
+ * are kept without their leading zeros along with their number of digits
+ * so that they compare by value whatever their size.
+ */
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum NaturalChunk {
+    Number(usize, String),
+    Text(String),
+}
+
+// Splits a node name into text and number chunks: `rack01-node12` gives
+// `rack`, `1`, `-node`, `12`.
+fn natural_key(name: &str) -> Vec<NaturalChunk> {
+    let mut key = Vec::new();
+    let mut rest = name;
+
+    while let Some(c) = rest.chars().next() {
+        let is_digit = c.is_ascii_digit();
+        let end = rest.find(|c: char| c.is_ascii_digit() != is_digit).unwrap_or(rest.len());
+        let (chunk, next) = rest.split_at(end);
+        if is_digit {
+            let number = chunk.trim_start_matches('0');
+            key.push(NaturalChunk::Number(number.len(), number.to_string()));
+        } else {
+            key.push(NaturalChunk::Text(chunk.to_string()));
+        }
+        rest = next;
+    }
+    key
+}
+
+/// Statistics about how well a NodeSet is folded.
+/// * `nodes` is the number of nodes of the NodeSet once expanded
+/// * `ranges` is the number of Ranges used to define the NodeSet
+/// * `compression` is the ratio between `nodes` and the number of
+///   tokens (Ranges or nodes without any range) of the NodeSet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FoldStats {
+    pub nodes: u64,
+    pub ranges: usize,
+    pub compression: f64,
+}
+
+/// Helps building a NodeSet from code without having to concatenate
+/// strings:
+/// ```rust
+/// use nodeset::NodeSet;
+/// let nodeset = NodeSet::builder().add_node("node[1-5]").unwrap().add_node("node[6-10]").unwrap().build();
+/// assert_eq!(nodeset.to_string(), "node[1-10]");
+/// ```
+#[derive(Debug, Default)]
+pub struct NodeSetBuilder {
+    set: Vec<Node>,
+}
+
+impl NodeSetBuilder {
+    /// Adds the node (`node[1-10]` or `gpu4` for instance) to the future NodeSet
+    pub fn add_node(mut self, node: &str) -> Result<Self, NodeErrorType> {
+        self.set.push(Node::new(node)?);
+        Ok(self)
+    }
+
+    /// Adds all nodes from `nodeset` to the future NodeSet
+    #[allow(clippy::should_implement_trait)]
+    pub fn add(mut self, nodeset: NodeSet) -> Self {
+        self.set.extend(nodeset.set);
+        self
+    }
+
+    /// Builds the NodeSet merging the redundant node definitions.
+    pub fn build(self) -> NodeSet {
+        NodeSet {
+            set: self.set,
+            current_iter_index: None,
+            cache: None,
+        }
+        .optimize()
+    }
+}
+
+/// Options used to parse and display a NodeSet with
+/// `NodeSet::new_with_config()` and `NodeSet::to_string_with_config()`:
+/// * `max_nodes` is the maximum number of nodes the NodeSet may have
+/// * `strict` refuses nodesets where a node is defined more than once
+///   such as `node[1-5],node4`
+/// * `default_pad` is the minimal padding of the numbers of the NodeSet
+/// * `syntax` holds the characters used to write the rangesets of the
+///   nodes (`node{1-4;8}` with `{`, `}` and `;`, see ParseOptions)
+/// ```rust
+/// use nodeset::{NodeSet, NodeSetConfig};
+/// let config = NodeSetConfig { default_pad: 2, ..NodeSetConfig::default() };
+/// let nodeset = NodeSet::new_with_config("node[8-10]", &config).unwrap();
+/// assert_eq!(nodeset.to_string(), "node[08-10]");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NodeSetConfig {
+    pub max_nodes: Option<u64>,
+    pub strict: bool,
+    pub default_pad: usize,
+    pub syntax: ParseOptions,
 }
 
 impl NodeSet {
-    /// Counts the number of node in the NodeSet
-    pub fn len(&self) -> usize {
-        self.set.iter().map(|node| node.len() as usize).sum()
+    /// Returns a NodeSetBuilder to build a NodeSet node by node
+    pub fn builder() -> NodeSetBuilder {
+        NodeSetBuilder::default()
+    }
+
+    /// Counts the number of node in the NodeSet. The count saturates
+    /// at u64::MAX instead of overflowing.
+    pub fn len(&self) -> u64 {
+        self.set.iter().fold(0, |acc: u64, node| acc.saturating_add(node.len()))
     }
 
     /// Tells whether a NodeSet is empty or not.
@@ -42,6 +154,19 @@ impl NodeSet {
         self.set.is_empty()
     }
 
+    /// Writes the node names of the NodeSet separated by `sep` into `w`
+    /// as they are generated: no name is kept in memory so that huge
+    /// nodesets such as `node[1-10000000]` can be expanded into a file.
+    pub fn expand_to_writer<W: io::Write>(&self, sep: &str, w: &mut W) -> io::Result<()> {
+        for (index, name) in self.names().enumerate() {
+            if index > 0 {
+                w.write_all(sep.as_bytes())?;
+            }
+            w.write_all(name.as_bytes())?;
+        }
+        Ok(())
+    }
+
     /// Transforms a nodeset (String) into a string by expanding the Node structures
     pub fn expand<S: AsRef<str>>(&self, separator: S) -> Result<String, Box<dyn Error>> {
         let sep = separator.as_ref();
@@ -55,6 +180,245 @@ impl NodeSet {
         Ok(all)
     }
 
+    /// Same as `expand()` but the expanded String is kept in the NodeSet
+    /// so that another call with the same separator does not expand
+    /// the whole NodeSet again. Any modification of the NodeSet (`append()`,
+    /// `retain()`) invalidates the cached value.
+    pub fn expand_cached<S: AsRef<str>>(&mut self, separator: S) -> Result<String, Box<dyn Error>> {
+        let sep = separator.as_ref();
+
+        if let Some((cached_sep, expanded)) = &self.cache {
+            if cached_sep == sep {
+                return Ok(expanded.to_string());
+            }
+        }
+
+        let expanded = self.expand(sep)?;
+        self.cache = Some((sep.to_string(), expanded.to_string()));
+
+        Ok(expanded)
+    }
+
+    /// Appends all nodes of `other` into self NodeSet and
+    /// merges the redundant node definitions.
+    pub fn append(&mut self, other: &Self) {
+        self.set.extend(other.set.clone());
+        self.set = self.optimize().set;
+        self.current_iter_index = None;
+        self.cache = None;
+    }
+
+    /// Parses hostnames and adds them to the NodeSet that is folded again.
+    /// It stops at the first hostname that can not be parsed and returns
+    /// its error: the NodeSet is left untouched in that case.
+    pub fn try_extend<I: IntoIterator<Item = String>>(&mut self, iter: I) -> Result<(), NodeErrorType> {
+        let nodes = iter.into_iter().map(|hostname| Node::new(hostname.trim())).collect::<Result<Vec<Node>, NodeErrorType>>()?;
+
+        self.extend(nodes);
+        Ok(())
+    }
+
+    /// Retains only the nodes for which `f` returns true.
+    pub fn retain<F: FnMut(&Node) -> bool>(&mut self, f: F) {
+        self.set.retain(f);
+        self.current_iter_index = None;
+        self.cache = None;
+    }
+
+    // Lazily generates all node names of the NodeSet without modifying it
+    fn names(&self) -> impl Iterator<Item = String> + '_ {
+        self.set.iter().flat_map(|node| node.fresh_clone())
+    }
+
+    /// Returns statistics about the folding of the NodeSet: `node[1-100]`
+    /// has a compression of 100 whereas `node[1,3,5]` only has 1.
+    pub fn fold_stats(&self) -> FoldStats {
+        let nodes = self.len();
+        let ranges = self.set.iter().map(|node| node.range_count()).sum();
+        let tokens: usize = self.set.iter().map(|node| node.range_count().max(1)).sum();
+        let compression = if tokens == 0 {
+            0.0
+        } else {
+            nodes as f64 / tokens as f64
+        };
+
+        FoldStats {
+            nodes,
+            ranges,
+            compression,
+        }
+    }
+
+    /// Expands the NodeSet with node names sorted in natural order and
+    /// separated by `sep`: `node[10,2,1]` gives `node1,node2,node10` and
+    /// `node1-cpu10` comes after `node1-cpu2`. Unlike
+    /// `expand_unique_sorted()` node names defined twice are kept.
+    pub fn sorted_expand(&self, sep: &str) -> Result<String, Box<dyn Error>> {
+        if usize::try_from(self.len()).is_err() {
+            return Err(format!("Too many nodes in {self} to expand them").into());
+        }
+
+        let mut names: Vec<String> = self.names().collect();
+        names.sort_by_cached_key(|name| natural_key(name));
+
+        Ok(names.join(sep))
+    }
+
+    /// Expands the NodeSet into a vector of unique node names sorted in
+    /// natural order: numbers in names are compared by their value so
+    /// that `node2` comes before `node10`.
+    pub fn expand_unique_sorted(&self) -> Vec<String> {
+        let names: BTreeSet<(Vec<NaturalChunk>, String)> = self.names().map(|name| (natural_key(&name), name)).collect();
+
+        names.into_iter().map(|(_, name)| name).collect()
+    }
+
+    /// Tells whether `hostname` is one of the nodes of the NodeSet:
+    /// `node[1-10],gpu[1-4]` contains `gpu3`. No node name is generated.
+    pub fn contains(&self, hostname: &str) -> bool {
+        self.set.iter().any(|node| node.contains(hostname))
+    }
+
+    /// Tells whether all the nodes of self NodeSet are nodes of the other
+    /// NodeSet: `node[2-4]` is a subset of `node[1-10],gpu[1-2]`. An
+    /// empty NodeSet is a subset of any NodeSet.
+    pub fn is_subset_of(&self, other: &NodeSet) -> bool {
+        self.set.iter().all(|node| {
+            let mut remaining = vec![node.fresh_clone()];
+            for o in &other.set {
+                remaining = remaining.iter().flat_map(|n| n.difference_parts(o)).collect();
+            }
+            remaining.is_empty()
+        })
+    }
+
+    /// Returns the template (`node{}` for instance) shared by all the
+    /// nodes of the NodeSet or None when the nodes have different
+    /// templates (`node[1-5],gpu[1-2]`) or when the NodeSet is empty.
+    pub fn common_template(&self) -> Option<String> {
+        let template = self.set.first()?.name_template();
+
+        if self.set.iter().all(|node| node.name_template() == template) {
+            Some(template.to_string())
+        } else {
+            None
+        }
+    }
+
+    /// Returns, for each node template (`node{}` for instance), the sorted
+    /// and merged intervals of the values of the first dimension of the
+    /// nodes: `node[1-5,10-12]` gives `[("node{}", [(1, 5), (10, 12)])]`.
+    /// Nodes without any dimension (`login`) are not returned.
+    pub fn numeric_intervals(&self) -> Vec<(String, Vec<(u64, u64)>)> {
+        #[rustfmt::skip]
+        let intervals = self.set.iter()
+            .filter_map(|node| node.dimension(0).map(|set| (node.name_template().to_string(), set.intervals())))
+            .collect();
+
+        intervals
+    }
+
+    /// Returns the `n` first node names of the NodeSet. Nodes are
+    /// generated lazily so the rest of the NodeSet is never expanded.
+    pub fn preview(&self, n: usize) -> Vec<String> {
+        self.names().take(n).collect()
+    }
+
+    /// Returns a NodeSet made of the `max` first nodes (in iteration
+    /// order) of self NodeSet: `node[1-100]` truncated to 10 gives
+    /// `node[1-10]`.
+    pub fn truncate(&self, max: u64) -> Self {
+        let mut set: Vec<Node> = vec![];
+        let mut remaining = max;
+
+        for node in &self.set {
+            if remaining == 0 {
+                break;
+            }
+            let len = node.len();
+            if len <= remaining {
+                set.push(node.fresh_clone());
+                remaining -= len;
+            } else {
+                for name in node.fresh_clone().take(remaining as usize) {
+                    // name comes from a valid node so it is a valid node
+                    set.push(Node::new(name).unwrap());
+                }
+                remaining = 0;
+            }
+        }
+
+        Self {
+            set,
+            current_iter_index: None,
+            cache: None,
+        }
+        .optimize()
+    }
+
+    /// Returns the node name at position `index` in iteration order (the
+    /// first name being at position 0) without iterating over the names
+    /// that come before it.
+    pub fn nth_node(&self, index: usize) -> Option<String> {
+        let mut index = index as u64;
+
+        for node in &self.set {
+            let len = node.len();
+            if index < len {
+                return node.nth_name(index);
+            }
+            index -= len;
+        }
+        None
+    }
+
+    /// Splits the NodeSet into `n` NodeSets with the same number of nodes
+    /// (give or take one), the first ones being the largest: `node[1-10]`
+    /// split in 3 gives `node[1-4]`, `node[5-7]` and `node[8-10]`. When
+    /// `n` is greater than the number of nodes the last NodeSets are
+    /// empty. Splitting in 0 gives no NodeSet at all.
+    pub fn split(&self, n: usize) -> Vec<Self> {
+        if n == 0 {
+            return vec![];
+        }
+
+        let len = self.len();
+        let chunk = len / n as u64;
+        let bigger = len % n as u64;
+        let mut names = self.names();
+
+        (0..n as u64)
+            .map(|index| {
+                let size = if index < bigger {
+                    chunk + 1
+                } else {
+                    chunk
+                };
+                // names come from a valid NodeSet so they are valid nodes
+                let set = names.by_ref().take(size as usize).map(|name| Node::new(name).unwrap()).collect();
+
+                Self {
+                    set,
+                    current_iter_index: None,
+                    cache: None,
+                }
+                .optimize()
+            })
+            .collect()
+    }
+
+    /// Pairs the node names of self NodeSet with the ones of the other
+    /// NodeSet in iteration order: `node[1-2]` and `backup[1-2]` gives
+    /// `[("node1", "backup1"), ("node2", "backup2")]`. Both NodeSets must
+    /// have the same number of nodes.
+    pub fn zip_names(&self, other: &Self) -> Result<Vec<(String, String)>, Box<dyn Error>> {
+        if self.len() != other.len() {
+            return Err(format!("Cannot pair {} nodes with {} nodes !", self.len(), other.len()).into());
+        }
+
+        Ok(self.names().zip(other.names()).collect())
+    }
+
     /// Intersection of NodeSet with an other NodeSet.
     pub fn intersection(&self, other: &Self) -> Self {
         let mut set = vec![];
@@ -71,18 +435,61 @@ impl NodeSet {
         Self {
             set,
             current_iter_index: None,
+            cache: None,
+        }
+    }
+
+    /// Difference of NodeSet with an other NodeSet: the nodes of self
+    /// that are not in the other NodeSet. `node[1-10],gpu[1-2]` minus
+    /// `node[3-5],gpu[1-2]` gives `node[1-2,6-10]`.
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut set: Vec<Node> = self.set.iter().map(|node| node.fresh_clone()).collect();
+
+        for excluded in &other.set {
+            set = set.iter().flat_map(|node| node.difference_parts(excluded)).collect();
         }
+
+        Self {
+            set,
+            current_iter_index: None,
+            cache: None,
+        }
+        .optimize()
     }
 
-    /// Union of two NodeSets
+    /// Intersection of NodeSet with an other NodeSet. Also returns the
+    /// templates (`gpu-node{}` for instance) of the nodes of self NodeSet
+    /// that have no node with the same template in the other NodeSet and
+    /// that were thus left out of the intersection.
+    pub fn intersection_report(&self, other: &Self) -> (Self, Vec<String>) {
+        #[rustfmt::skip]
+        let unmatched = self.set.iter()
+            .filter(|node| !other.set.iter().any(|o| o.name_template() == node.name_template()))
+            .map(|node| node.name_template().to_string())
+            .collect();
+
+        (self.intersection(other), unmatched)
+    }
+
+    /// Union of two NodeSets. `a.union(&b)` and `b.union(&a)` are the
+    /// same NodeSet, nodes being in the same order: the NodeSet whose node
+    /// templates (`node{}`, `gpu{}`, ...) come first in lexicographic order
+    /// gives the order of the nodes.
     pub fn union(&self, other: &Self) -> Self {
+        let (first, second) = if self.set.iter().map(Node::name_template).le(other.set.iter().map(Node::name_template)) {
+            (self, other)
+        } else {
+            (other, self)
+        };
+
         // Add all node definitions to the internal vec and optimize it all
-        let mut set = self.set.clone();
-        set.extend(other.set.clone());
+        let mut set = first.set.clone();
+        set.extend(second.set.clone());
 
         Self {
             set,
             current_iter_index: None,
+            cache: None,
         }
         .optimize()
     }
@@ -99,86 +506,260 @@ impl NodeSet {
             #[rustfmt::skip]
             let matches: Vec<(usize, Result<_, _>)> = optimized_set.iter()
                 .enumerate()
-                .map(|(idx, n)| (idx, n.union(&node)))
+                .map(|(idx, n)| (idx, n.union(node)))
                 .filter(|(_, res)| res.is_ok())
                 .collect();
 
             // Because we start from an empty vec, either the node has a match and gets merged or
             // it does not and gets added to the optimized set
-            match matches.len() {
-                0 => optimized_set.push(node.clone()),
-                1 => {
-                    let (index, union) = matches.first().unwrap();
-                    optimized_set[index.clone()] = union.as_ref().unwrap().clone();
-                }
-                _ => unreachable!(),
+            // Multi-dimension nodes may be merged with more than one node: the first one is used
+            match matches.first() {
+                None => optimized_set.push(node.fresh_clone()),
+                Some((index, union)) => optimized_set[*index] = union.as_ref().unwrap().fresh_clone(),
             }
         }
 
         Self {
             set: optimized_set,
             current_iter_index: None,
+            cache: None,
         }
     }
 
     pub fn new<S: AsRef<str>>(string: S) -> Result<Self, NodeErrorType> {
-        // Create a copy of the original string to butcher
-        let mut stencil = string.as_ref().to_string();
-
-        // Let the nodes figure out the rangesets, then overwrite them in the copy
-        let (_, rangesets) = Node::capture_with_regex(string.as_ref())?;
-        for rs in rangesets {
-            unsafe {
-                stencil = stencil.replace(&rs, &String::from_utf8_unchecked(vec![b'_'; rs.len()]));
+        Ok(Self {
+            set: NodeSet::parse_nodes(string.as_ref())?,
+            current_iter_index: None,
+            cache: None,
+        }
+        .optimize())
+    }
+
+    /// Creates a NodeSet from `string` following the options of `config`
+    /// (see NodeSetConfig).
+    pub fn new_with_config(string: &str, config: &NodeSetConfig) -> Result<Self, NodeErrorType> {
+        let set: Vec<Node> = NodeSet::parse_nodes_with_syntax(string, &config.syntax)?.iter().map(|node| node.with_min_pad(config.default_pad)).collect();
+
+        if config.strict {
+            for (index, node) in set.iter().enumerate() {
+                for other in &set[index + 1..] {
+                    let remaining: u64 = node.difference_parts(other).iter().map(|part| part.len()).sum();
+                    if remaining != node.len() {
+                        return Err(NodeErrorType::Regular(ErrorKind::DuplicateNodes(format!("{node},{other}"))));
+                    }
+                }
             }
         }
 
-        // We can now split using the commas left in the stencil, as they are vetted and not part
-        // of a rangeset definition
-        let mut set = vec![];
-        let mut cursor = 0;
-        while cursor < stencil.len() {
-            let range;
-
-            match stencil[cursor..].find(',') {
-                Some(comma_offset) => {
-                    range = cursor..(cursor + comma_offset);
-                    cursor += comma_offset + 1
+        let nodeset = Self {
+            set,
+            current_iter_index: None,
+            cache: None,
+        }
+        .optimize();
+
+        match config.max_nodes {
+            Some(max) if nodeset.len() > max => Err(NodeErrorType::Regular(ErrorKind::TooManyNodes(max))),
+            _ => Ok(nodeset),
+        }
+    }
+
+    /// Displays the NodeSet using the syntax of `config`.
+    pub fn to_string_with_config(&self, config: &NodeSetConfig) -> String {
+        let syntax = &config.syntax;
+        let mut depth = 0;
+
+        self.to_string()
+            .chars()
+            .map(|c| match c {
+                '[' => {
+                    depth += 1;
+                    syntax.opening
                 }
-                None => {
-                    range = cursor..stencil.len();
-                    cursor = usize::max_value();
+                ']' => {
+                    depth -= 1;
+                    syntax.closing
                 }
+                ',' if depth > 0 => syntax.separator,
+                c => c,
+            })
+            .collect()
+    }
+
+    // Splits `string` on the commas that are not part of a rangeset
+    // (ie not between brackets) and parses each node found. Indices come
+    // from char_indices() so that node names may contain any UTF-8 char.
+    // Spaces around nodes are ignored as are empty nodes (`node[1-4],`).
+    fn parse_nodes(string: &str) -> Result<Vec<Node>, NodeErrorType> {
+        NodeSet::parse_nodes_with_syntax(string, &ParseOptions::default())
+    }
+
+    // Same as parse_nodes() for nodes written with `syntax`
+    fn parse_nodes_with_syntax(string: &str, syntax: &ParseOptions) -> Result<Vec<Node>, NodeErrorType> {
+        let mut segments = vec![];
+        let mut depth = 0;
+        let mut begin = 0;
+
+        for (index, c) in string.char_indices() {
+            match c {
+                c if c == syntax.opening => depth += 1,
+                c if c == syntax.closing => depth -= 1,
+                ',' if depth == 0 => {
+                    segments.push((begin, &string[begin..index]));
+                    begin = index + 1;
+                }
+                _ => (),
             }
+        }
+        segments.push((begin, &string[begin..]));
 
-            set.push(Node::new(&string.as_ref()[range])?);
+        // Offsets of the errors are given from the beginning of `string`
+        #[rustfmt::skip]
+        let nodes = segments.iter()
+            .map(|(begin, segment)| (begin + segment.len() - segment.trim_start().len(), segment.trim()))
+            .filter(|(_, segment)| !segment.is_empty())
+            .map(|(offset, segment)| Node::new_with_syntax(segment, syntax).map_err(|e| e.shifted(offset)))
+            .collect();
+        nodes
+    }
+
+    /// Creates a NodeSet from `string` without the nodes of `exclude`:
+    /// `node[1-10]` excluding `node[3-5]` gives `node[1-2,6-10]`.
+    pub fn new_excluding(string: &str, exclude: &NodeSet) -> Result<Self, NodeErrorType> {
+        Ok(NodeSet::new(string)?.difference(exclude))
+    }
+
+    /// Creates a NodeSet from a hostlist such as `node[01-10],gpu[1-4,8]`.
+    /// This is `NodeSet::new()` refusing steps (`node[1-10/2]`) as
+    /// hostlists do not have them.
+    pub fn from_hostlist(hostlist: &str) -> Result<Self, NodeErrorType> {
+        if hostlist.contains('/') {
+            return Err(NodeErrorType::Regular(ErrorKind::StepInHostlist(hostlist.to_string())));
         }
+        NodeSet::new(hostlist)
+    }
 
-        Ok(Self {
-            set,
-            current_iter_index: None,
+    /// Displays the NodeSet as a hostlist: `node[1-10/3],gpu[4-1]` gives
+    /// `node[1,4,7,10],gpu[1-4]`.
+    pub fn to_hostlist(&self) -> String {
+        let nodes: Vec<String> = self.set.iter().map(|node| node.to_hostlist()).collect();
+        nodes.join(",")
+    }
+
+    /// Parses a string containing independent nodesets separated by `;`
+    /// such as `node[1-2];gpu[1-4]` and returns one NodeSet per nodeset
+    /// found. `;` found between brackets are not considered as separators.
+    pub fn new_multi(string: &str) -> Result<Vec<Self>, NodeErrorType> {
+        let mut nodesets = vec![];
+        let mut depth = 0;
+        let mut begin = 0;
+
+        for (index, c) in string.char_indices() {
+            match c {
+                '[' => depth += 1,
+                ']' => depth -= 1,
+                ';' if depth == 0 => {
+                    nodesets.push(NodeSet::new(&string[begin..index]).map_err(|e| e.shifted(begin))?);
+                    begin = index + 1;
+                }
+                _ => (),
+            }
         }
-        .optimize())
+        nodesets.push(NodeSet::new(&string[begin..]).map_err(|e| e.shifted(begin))?);
+
+        Ok(nodesets)
     }
 }
 
 /// Iterator implementation for NodeSet to allow one to use `for n in node {...}` construction.
+/// Nodes of the NodeSet are iterated one after the other: `current_iter_index`
+/// is the index of the Node that is being iterated so that each call is O(1).
 impl Iterator for NodeSet {
     type Item = String;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let mut global = self.set.iter().flat_map(|node| node.clone().into_iter());
-
-        match self.current_iter_index {
+        let mut index = match self.current_iter_index {
+            Some(index) => index,
             None => {
-                self.current_iter_index = Some(1);
-                global.next()
+                // An empty NodeSet has nothing to iterate on
+                let node = self.set.first_mut()?;
+                *node = node.fresh_clone();
+                0
             }
-            Some(index) => {
-                self.current_iter_index = Some(index + 1);
-                global.skip(index).next()
+        };
+
+        while let Some(node) = self.set.get_mut(index) {
+            if let Some(name) = node.next() {
+                self.current_iter_index = Some(index);
+                return Some(name);
+            }
+
+            // This node is exhausted: the next one starts from its first name
+            index += 1;
+            if let Some(next) = self.set.get_mut(index) {
+                *next = next.fresh_clone();
             }
         }
+
+        self.current_iter_index = Some(index);
+        None
+    }
+}
+
+/// `a - b` is the difference of NodeSets `a` and `b`.
+impl Sub for NodeSet {
+    type Output = NodeSet;
+
+    fn sub(self, other: Self) -> Self::Output {
+        self.difference(&other)
+    }
+}
+
+/// `a & b` is the intersection of NodeSets `a` and `b`.
+impl BitAnd for NodeSet {
+    type Output = NodeSet;
+
+    fn bitand(self, other: Self) -> Self::Output {
+        self.intersection(&other).optimize()
+    }
+}
+
+/// `a | b` is the union of NodeSets `a` and `b`.
+impl BitOr for NodeSet {
+    type Output = NodeSet;
+
+    fn bitor(self, other: Self) -> Self::Output {
+        self.union(&other)
+    }
+}
+
+/// Builds a NodeSet from Nodes: `node1`, `node2` and `node3` give
+/// `node[1-3]`. Hostnames are parsed first so that an error is never
+/// lost: collecting `Node::new()` results gives a `Result<NodeSet, _>`.
+/// ```rust
+/// use nodeset::{Node, NodeSet};
+/// let nodeset: NodeSet = ["node1", "node2", "node3"].iter().map(Node::new).collect::<Result<_, _>>().unwrap();
+/// assert_eq!(nodeset.to_string(), "node[1-3]");
+/// assert!(["node1", "node[2"].iter().map(Node::new).collect::<Result<NodeSet, _>>().is_err());
+/// ```
+impl FromIterator<Node> for NodeSet {
+    fn from_iter<I: IntoIterator<Item = Node>>(iter: I) -> Self {
+        let mut nodeset = NodeSet {
+            set: vec![],
+            current_iter_index: None,
+            cache: None,
+        };
+        nodeset.extend(iter);
+        nodeset
+    }
+}
+
+/// Adds Nodes to the NodeSet and folds it again. Use
+/// `NodeSet::try_extend()` to add hostnames.
+impl Extend<Node> for NodeSet {
+    fn extend<I: IntoIterator<Item = Node>>(&mut self, iter: I) {
+        self.set.extend(iter);
+        *self = self.optimize();
     }
 }
 
@@ -221,6 +802,7 @@ fn test_nodeset_creation() {
         NodeSet {
             set: vec![node, gpu, apu],
             current_iter_index: None,
+            cache: None,
         }
     );
 }
@@ -235,6 +817,7 @@ fn test_nodeset_creation_optimize() {
         NodeSet {
             set: vec![node, gpu],
             current_iter_index: None,
+            cache: None,
         }
     );
 }
@@ -252,6 +835,24 @@ fn test_nodeset_union() {
     assert_eq!(format!("{}", a.union(&b)), "node[1-100],gpu-node[1-16/5],apu-node[1-1000]".to_string());
 }
 
+#[test]
+fn test_nodeset_union_is_deterministic() {
+    let a = NodeSet::new("node[1-10]").unwrap();
+    let b = NodeSet::new("node[5-20],gpu[1-2]").unwrap();
+    assert_eq!(a.union(&b).to_string(), "node[1-20],gpu[1-2]");
+    assert_eq!(b.union(&a).to_string(), "node[1-20],gpu[1-2]");
+
+    let a = NodeSet::new("node[1-10]").unwrap();
+    let b = NodeSet::new("gpu[1-2]").unwrap();
+    assert_eq!(a.union(&b), b.union(&a));
+    assert_eq!(a.union(&b).to_string(), "gpu[1-2],node[1-10]");
+
+    let a = NodeSet::new("node[1-2],gpu1").unwrap();
+    let b = NodeSet::new("node[5,9],gpu[3-4]").unwrap();
+    assert_eq!(a.union(&b), b.union(&a));
+    assert_eq!(a.union(&b).to_string(), "node[1-2,5-9/4],gpu[1-3/2,4]");
+}
+
 #[test]
 fn test_nodeset_intersection() {
     let a = NodeSet::new("node[1-50],gpu-node[1-20/5],apu-node[1-1000]").unwrap();
@@ -283,3 +884,461 @@ fn test_nodeset_equality() {
     let b = NodeSet::new("node[1-2],gpu-node[1-4/2],apu-node[4]").unwrap();
     assert_eq!(a, b);
 }
+
+#[test]
+fn test_nodeset_preview() {
+    let nodeset = NodeSet::new("node[1-1000000]").unwrap();
+    assert_eq!(nodeset.preview(3).join(","), "node1,node2,node3".to_string());
+
+    let nodeset = NodeSet::new("node[1-2],gpu-node[1-4/2]").unwrap();
+    assert_eq!(nodeset.preview(10), vec!["node1", "node2", "gpu-node1", "gpu-node3"]);
+}
+
+#[test]
+fn test_nodeset_expand_cached() {
+    let mut nodeset = NodeSet::new("node[1-2],gpu-node[1-4/2]").unwrap();
+    let first = nodeset.expand_cached(",").unwrap();
+    let second = nodeset.expand_cached(",").unwrap();
+    assert_eq!(first, "node1,node2,gpu-node1,gpu-node3".to_string());
+    assert_eq!(first, second);
+    assert_eq!(nodeset.expand_cached(" ").unwrap(), "node1 node2 gpu-node1 gpu-node3".to_string());
+
+    nodeset.append(&NodeSet::new("node[3]").unwrap());
+    assert_eq!(nodeset.expand_cached(" ").unwrap(), "node1 node2 node3 gpu-node1 gpu-node3".to_string());
+
+    nodeset.retain(|node| node.to_string() != "node[1-3]");
+    assert_eq!(nodeset.expand_cached(" ").unwrap(), "gpu-node1 gpu-node3".to_string());
+}
+
+#[test]
+fn test_nodeset_new_multi() {
+    let nodesets = NodeSet::new_multi("node[1-2];gpu[1-4]").unwrap();
+    assert_eq!(nodesets, vec![NodeSet::new("node[1-2]").unwrap(), NodeSet::new("gpu[1-4]").unwrap()]);
+
+    let nodesets = NodeSet::new_multi("node[1-2],node[5];gpu[1-4],cpu[1]").unwrap();
+    assert_eq!(nodesets.len(), 2);
+    assert_eq!(nodesets[0].to_string(), "node[1-2,5]".to_string());
+    assert_eq!(nodesets[1].to_string(), "gpu[1-4],cpu1".to_string());
+
+    assert!(NodeSet::new_multi("node[1-2];gpu[1-4/]").is_err());
+}
+
+#[test]
+fn test_nodeset_builder() {
+    let nodeset = NodeSet::builder().add_node("node[1-5]").unwrap().add_node("gpu[1-4]").unwrap().add_node("node[6-10]").unwrap().build();
+    assert_eq!(nodeset, NodeSet::new("node[1-10],gpu[1-4]").unwrap());
+
+    let nodeset = NodeSet::builder().add(NodeSet::new("node[1-2],gpu[1]").unwrap()).add_node("gpu2").unwrap().build();
+    assert_eq!(nodeset.to_string(), "node[1-2],gpu[1-2]".to_string());
+
+    assert!(NodeSet::builder().add_node("node[1-2/]").is_err());
+}
+
+#[test]
+fn test_nodeset_hostlist() {
+    let examples = ["node[01-10]", "node[1-3,5],gpu7", "tux[0-2,4-6]-ib[1-2]", "linux", "node[001-010,100]"];
+    for example in examples {
+        let nodeset = NodeSet::from_hostlist(example).unwrap();
+        assert_eq!(nodeset.to_hostlist(), example.to_string());
+    }
+
+    let nodeset = NodeSet::new("node[1-10/3],gpu[4-1],cpu[5]").unwrap();
+    assert_eq!(nodeset.to_hostlist(), "node[1,4,7,10],gpu[1-4],cpu5".to_string());
+
+    // Single values that would not be read back as the same dimension keep their brackets
+    for (example, hostlist) in [("n[1][6]", "n[1][6]"), ("rack[B]", "rack[B]"), ("rack[1-2]-node[5]", "rack[1-2]-node5")] {
+        let nodeset = NodeSet::new(example).unwrap();
+        assert_eq!(nodeset.to_hostlist(), hostlist.to_string());
+        assert_eq!(NodeSet::from_hostlist(hostlist).unwrap(), nodeset);
+    }
+
+    let err = NodeSet::from_hostlist("node[1-10/2]").unwrap_err();
+    assert_eq!(err, NodeErrorType::Regular(ErrorKind::StepInHostlist("node[1-10/2]".to_string())));
+    assert_eq!(err.to_string(), "steps are not allowed in a hostlist 'node[1-10/2]'");
+}
+
+#[test]
+fn test_nodeset_intersection_report() {
+    let a = NodeSet::new("node[1-50],gpu-node[1-20/5]").unwrap();
+    let b = NodeSet::new("node[50-100],cpu-node[1-20]").unwrap();
+    let (inter, unmatched) = a.intersection_report(&b);
+    assert_eq!(inter.to_string(), "node50".to_string());
+    assert_eq!(unmatched, vec!["gpu-node{}".to_string()]);
+
+    let (_, unmatched) = b.intersection_report(&a);
+    assert_eq!(unmatched, vec!["cpu-node{}".to_string()]);
+}
+
+#[test]
+fn test_nodeset_zip_names() {
+    let a = NodeSet::new("node[1-3]").unwrap();
+    let b = NodeSet::new("backup[1-3]").unwrap();
+    let pairs = a.zip_names(&b).unwrap();
+    assert_eq!(pairs, vec![("node1".to_string(), "backup1".to_string()), ("node2".to_string(), "backup2".to_string()), ("node3".to_string(), "backup3".to_string())]);
+
+    let c = NodeSet::new("backup[1-4]").unwrap();
+    assert!(a.zip_names(&c).is_err());
+}
+
+#[test]
+fn test_nodeset_fold_stats() {
+    let nodeset = NodeSet::new("node[1-100]").unwrap();
+    let stats = nodeset.fold_stats();
+    assert_eq!(stats.nodes, 100);
+    assert_eq!(stats.ranges, 1);
+    assert_eq!(stats.compression, 100.0);
+
+    let nodeset = NodeSet::new("node[1,3,5,7,9,11,13,17]").unwrap();
+    let stats = nodeset.fold_stats();
+    assert_eq!(stats.nodes, 8);
+    assert_eq!(stats.ranges, 8);
+    assert_eq!(stats.compression, 1.0);
+
+    let nodeset = NodeSet::new("node[1-10]-cpu[1-2],login").unwrap();
+    let stats = nodeset.fold_stats();
+    assert_eq!(stats.nodes, 21);
+    assert_eq!(stats.ranges, 2);
+    assert_eq!(stats.compression, 7.0);
+}
+
+#[test]
+fn test_nodeset_truncate() {
+    let nodeset = NodeSet::new("node[1-100]").unwrap();
+    assert_eq!(nodeset.truncate(10).to_string(), "node[1-10]".to_string());
+    assert_eq!(nodeset.truncate(1000).to_string(), "node[1-100]".to_string());
+    assert!(nodeset.truncate(0).is_empty());
+
+    let nodeset = NodeSet::new("node[1-5],gpu[01-10/2],cpu[1-3]").unwrap();
+    assert_eq!(nodeset.truncate(8).to_string(), "node[1-5],gpu[01-05/2]".to_string());
+    assert_eq!(nodeset.truncate(8).len(), 8);
+}
+
+#[test]
+fn test_nodeset_empty_iteration() {
+    let a = NodeSet::new("node[1-10]").unwrap();
+    let b = NodeSet::new("node[20-30]").unwrap();
+    let mut inter = a.intersection(&b);
+    assert!(inter.is_empty());
+    assert_eq!(inter.next(), None);
+    assert_eq!(inter.next(), None);
+    assert_eq!(inter.current_iter_index, None);
+}
+
+#[test]
+fn test_nodeset_expand_unique_sorted() {
+    let nodeset = NodeSet::new("node[10-8],gpu[2-1],node[9-12]").unwrap();
+    assert_eq!(nodeset.expand_unique_sorted(), vec!["gpu1", "gpu2", "node8", "node9", "node10", "node11", "node12"]);
+
+    let nodeset = NodeSet::new("rack[10,2]-node[3,1],rack[009-008]-gpu1").unwrap();
+    assert_eq!(nodeset.expand_unique_sorted(), vec!["rack2-node1", "rack2-node3", "rack008-gpu1", "rack009-gpu1", "rack10-node1", "rack10-node3"]);
+}
+
+#[test]
+fn test_nodeset_new_excluding() {
+    let exclude = NodeSet::new("node[3-5]").unwrap();
+    let nodeset = NodeSet::new_excluding("node[1-10]", &exclude).unwrap();
+    assert_eq!(nodeset.to_string(), "node[1-2,6-10]");
+
+    let exclude = NodeSet::new("node[1-20],a1-b1").unwrap();
+    let nodeset = NodeSet::new_excluding("node[1-10],gpu[1-2],a[1-2]-b[1-2]", &exclude).unwrap();
+    assert_eq!(nodeset.expand(",").unwrap(), "gpu1,gpu2,a2-b1,a2-b2,a1-b2");
+    assert_eq!(nodeset, NodeSet::new("node[1-10],gpu[1-2],a[1-2]-b[1-2]").unwrap().difference(&exclude));
+
+    assert!(NodeSet::new_excluding("node[1-", &exclude).is_err());
+}
+
+#[test]
+fn test_nodeset_len_above_u32() {
+    let nodeset = NodeSet::new("node[0-99999]-cpu[0-99999],gpu[1-4]").unwrap();
+    assert_eq!(nodeset.len(), 10_000_000_004_u64);
+}
+
+#[test]
+fn test_nodeset_numeric_intervals() {
+    let nodeset = NodeSet::new("node[1-5,10-12]").unwrap();
+    assert_eq!(nodeset.numeric_intervals(), vec![("node{}".to_string(), vec![(1, 5), (10, 12)])]);
+
+    let nodeset = NodeSet::new("login,gpu[8-6]-cpu[1-2]").unwrap();
+    assert_eq!(nodeset.numeric_intervals(), vec![("gpu{}-cpu{}".to_string(), vec![(6, 8)])]);
+}
+
+#[test]
+fn test_nodeset_iteration_linear() {
+    let nodeset = NodeSet::new("node[1-1000],gpu[1-2]").unwrap();
+    let names: Vec<String> = nodeset.collect();
+    assert_eq!(names.len(), 1002);
+    assert_eq!(names[..2], ["node1", "node2"]);
+    assert_eq!(names[999..], ["node1000", "gpu1", "gpu2"]);
+
+    // Iterating this NodeSet with a quadratic algorithm would not end in a reasonable time
+    let nodeset = NodeSet::new("node[1-300000]").unwrap();
+    assert_eq!(nodeset.last(), Some("node300000".to_string()));
+
+    let a = NodeSet::new("node[1-4]").unwrap();
+    let b = NodeSet::new("node[3-6]").unwrap();
+    assert_eq!(a.intersection(&b).collect::<Vec<String>>(), vec!["node3", "node4"]);
+}
+
+#[test]
+fn test_nodeset_common_template() {
+    let nodeset = NodeSet::new("node[1-5],node[10-20]").unwrap();
+    assert_eq!(nodeset.common_template(), Some("node{}".to_string()));
+
+    let nodeset = NodeSet::new("node[1-5],gpu[1-2]").unwrap();
+    assert_eq!(nodeset.common_template(), None);
+
+    let nodeset = NodeSet::new("rack[1-2]-node[1-5],rack[a-b]-node[1-2]").unwrap();
+    assert_eq!(nodeset.common_template(), Some("rack{}-node{}".to_string()));
+}
+
+#[test]
+fn test_nodeset_creation_optimize_dimensions() {
+    let nodeset = NodeSet::new("rack1-node[1-2],rack2-node[1-2],rack3-node1").unwrap();
+    assert_eq!(nodeset.to_string(), "rack[1-2]-node[1-2],rack3-node1");
+    assert_eq!(nodeset.len(), 5);
+
+    // a1-b1 may be merged with both a1-b[1-2] and a[2-3]-b1
+    let nodeset = NodeSet::new("a1-b[1-2],a[2-3]-b1,a1-b1").unwrap();
+    assert_eq!(nodeset.to_string(), "a1-b[1-2],a[2-3]-b1");
+}
+
+#[test]
+fn test_nodeset_contains() {
+    let nodeset = NodeSet::new("node[1-10],gpu[1-4]").unwrap();
+    assert!(nodeset.contains("gpu3"));
+    assert!(nodeset.contains("node10"));
+    assert!(!nodeset.contains("gpu5"));
+    assert!(!nodeset.contains("cpu1"));
+}
+
+#[test]
+fn test_nodeset_is_subset_of() {
+    let a = NodeSet::new("node[2-4]").unwrap();
+    let b = NodeSet::new("node[1-10],gpu[1-2]").unwrap();
+    assert!(a.is_subset_of(&b));
+    assert!(!b.is_subset_of(&a));
+    assert!(a.is_subset_of(&a));
+
+    // overlapping
+    let a = NodeSet::new("node[8-12]").unwrap();
+    assert!(!a.is_subset_of(&b));
+
+    // disjoint
+    let a = NodeSet::new("cpu[1-2]").unwrap();
+    assert!(!a.is_subset_of(&b));
+
+    // covered by several nodes of the other NodeSet
+    let a = NodeSet::new("a[1-2]-b[1-2]").unwrap();
+    let b = NodeSet::new("a1-b[1-2],a2-b[1-3]").unwrap();
+    assert!(a.is_subset_of(&b));
+
+    assert!(NodeSet::new("").unwrap().is_subset_of(&b));
+}
+
+#[test]
+fn test_nodeset_new_with_config() {
+    let config = NodeSetConfig {
+        max_nodes: Some(10),
+        strict: true,
+        ..NodeSetConfig::default()
+    };
+
+    let nodeset = NodeSet::new_with_config("node[1-5],gpu[1-5]", &config).unwrap();
+    assert_eq!(nodeset.len(), 10);
+
+    let err = NodeSet::new_with_config("node[1-6],gpu[1-5]", &config).unwrap_err();
+    assert_eq!(err.to_string(), "too many nodes in nodeset (10 at most)");
+
+    let err = NodeSet::new_with_config("node[1-5],node4", &config).unwrap_err();
+    assert_eq!(err.to_string(), "nodes defined more than once 'node[1-5],node4'");
+
+    // Without strict mode duplicates are merged
+    let config = NodeSetConfig {
+        strict: false,
+        ..config
+    };
+    assert_eq!(NodeSet::new_with_config("node[1-5],node4", &config).unwrap().to_string(), "node[1-5]");
+
+    let config = NodeSetConfig {
+        default_pad: 3,
+        syntax: ParseOptions {
+            opening: '{',
+            closing: '}',
+            ..ParseOptions::default()
+        },
+        ..NodeSetConfig::default()
+    };
+    let nodeset = NodeSet::new_with_config("node{8-10,0042},gpu1", &config).unwrap();
+    assert_eq!(nodeset.to_string(), "node[008-010,0042],gpu001");
+    assert_eq!(nodeset.to_string_with_config(&config), "node{008-010,0042},gpu001");
+
+    let config = NodeSetConfig {
+        syntax: ParseOptions {
+            opening: '(',
+            closing: ')',
+            separator: ';',
+        },
+        ..NodeSetConfig::default()
+    };
+    let nodeset = NodeSet::new_with_config("rack(1;3)-node(1-4;8),login", &config).unwrap();
+    assert_eq!(nodeset.to_string(), "rack[1,3]-node[1-4,8],login");
+    assert_eq!(nodeset.to_string_with_config(&config), "rack(1;3)-node(1-4;8),login");
+    assert!(NodeSet::new_with_config("node[1-4]", &config).is_err());
+}
+
+#[test]
+fn test_nodeset_difference() {
+    let a = NodeSet::new("node[1-10],gpu[1-2]").unwrap();
+    let b = NodeSet::new("node[3-5],gpu[1-2],cpu1").unwrap();
+    assert_eq!(a.difference(&b).to_string(), "node[1-2,6-10]");
+    assert_eq!(b.difference(&a).to_string(), "cpu1");
+    assert!(a.difference(&a).is_empty());
+
+    let a = NodeSet::new("node[1-10],gpu[1-2]").unwrap();
+    let b = NodeSet::new("node[3-5]").unwrap();
+    assert_eq!((a - b).to_string(), "node[1-2,6-10],gpu[1-2]");
+}
+
+#[test]
+fn test_nodeset_operators() {
+    let a = NodeSet::new("node[1-10],gpu[1-2]").unwrap();
+    let b = NodeSet::new("node[5-20],cpu1").unwrap();
+    assert_eq!((a & b).to_string(), "node[5-10]");
+
+    let a = NodeSet::new("node[1-10],gpu[1-2]").unwrap();
+    let b = NodeSet::new("node[5-20],cpu1").unwrap();
+    // node{},cpu{} comes before node{},gpu{}: b gives the order of the nodes
+    assert_eq!((a | b).to_string(), "node[1-20],cpu1,gpu[1-2]");
+
+    let a = NodeSet::new("node[1-10],gpu[1-2]").unwrap();
+    let b = NodeSet::new("node[5-20],cpu1").unwrap();
+    assert_eq!((a - b).to_string(), "node[1-4],gpu[1-2]");
+}
+
+#[test]
+fn test_nodeset_utf8_names() {
+    let nodeset = NodeSet::new("nœud[1-3],gpu-é1,ünïcødé").unwrap();
+    assert_eq!(nodeset.to_string(), "nœud[1-3],gpu-é1,ünïcødé");
+    assert_eq!(nodeset.expand(" ").unwrap(), "nœud1 nœud2 nœud3 gpu-é1 ünïcødé");
+
+    let nodeset = NodeSet::new("é[1-2],é[3-4]").unwrap();
+    assert_eq!(nodeset.to_string(), "é[1-4]");
+}
+
+#[test]
+fn test_nodeset_whitespace_and_trailing_commas() {
+    let nodeset = NodeSet::new("node[1-4], node[5-8]").unwrap();
+    assert_eq!(nodeset.to_string(), "node[1-8]");
+
+    let nodeset = NodeSet::new(" node[1-4] ,\tgpu[1-2] ").unwrap();
+    assert_eq!(nodeset.to_string(), "node[1-4],gpu[1-2]");
+
+    let nodeset = NodeSet::new("node[1-4],").unwrap();
+    assert_eq!(nodeset.len(), 4);
+    assert_eq!(nodeset.expand(",").unwrap(), "node1,node2,node3,node4");
+
+    let nodeset = NodeSet::new("node1,,node2, ,").unwrap();
+    assert_eq!(nodeset.to_string(), "node[1-2]");
+}
+
+#[test]
+fn test_nodeset_split() {
+    let nodeset = NodeSet::new("node[1-10]").unwrap();
+    let parts: Vec<String> = nodeset.split(3).iter().map(|n| n.to_string()).collect();
+    assert_eq!(parts, vec!["node[1-4]", "node[5-7]", "node[8-10]"]);
+
+    let nodeset = NodeSet::new("node[1-4],gpu[1-4]").unwrap();
+    let parts: Vec<String> = nodeset.split(2).iter().map(|n| n.to_string()).collect();
+    assert_eq!(parts, vec!["node[1-4]", "gpu[1-4]"]);
+
+    let parts: Vec<String> = nodeset.split(4).iter().map(|n| n.to_string()).collect();
+    assert_eq!(parts, vec!["node[1-2]", "node[3-4]", "gpu[1-2]", "gpu[3-4]"]);
+
+    let nodeset = NodeSet::new("node[1-2]").unwrap();
+    let parts = nodeset.split(4);
+    assert_eq!(parts.len(), 4);
+    assert_eq!(parts[0].to_string(), "node1");
+    assert_eq!(parts[1].to_string(), "node2");
+    assert!(parts[2].is_empty());
+    assert!(parts[3].is_empty());
+
+    assert!(nodeset.split(0).is_empty());
+}
+
+#[test]
+fn test_nodeset_nth_node() {
+    let nodeset = NodeSet::new("node[1-3]-cpu[1-2],gpu[8-10/2],login").unwrap();
+    let names: Vec<String> = nodeset.names().collect();
+    assert_eq!(names.len(), 9);
+
+    for (index, name) in names.iter().enumerate() {
+        assert_eq!(nodeset.nth_node(index).as_ref(), Some(name));
+    }
+    assert_eq!(nodeset.nth_node(9), None);
+
+    let nodeset = NodeSet::new("node[1-1000000]").unwrap();
+    assert_eq!(nodeset.nth_node(499999), Some("node500000".to_string()));
+}
+
+#[test]
+fn test_nodeset_expand_to_writer() {
+    let nodeset = NodeSet::new("node[1-10/3],gpu-node[01-02]-cpu[1-2],login").unwrap();
+    let mut out: Vec<u8> = Vec::new();
+    nodeset.expand_to_writer(",", &mut out).unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), nodeset.expand(",").unwrap());
+
+    let mut out: Vec<u8> = Vec::new();
+    NodeSet::new("").unwrap().expand_to_writer(",", &mut out).unwrap();
+    assert!(out.is_empty());
+}
+
+#[test]
+fn test_nodeset_empty_brackets() {
+    // Offsets are given from the beginning of the nodeset
+    let err = NodeSet::new("node[1-4],gpu[]").unwrap_err();
+    assert!(matches!(err, NodeErrorType::Regular(ErrorKind::EmptyRangeSet(13))));
+    let err = NodeSet::new("node[1-4],  gpu[1-").unwrap_err();
+    assert!(matches!(err, NodeErrorType::Regular(ErrorKind::UnbalancedBrackets(_, 15))));
+    let err = NodeSet::new_multi("node[1-4];gpu[]").unwrap_err();
+    assert!(matches!(err, NodeErrorType::Regular(ErrorKind::EmptyRangeSet(13))));
+}
+
+#[test]
+fn test_nodeset_from_iter() {
+    let hosts = ["node1", "node2", "node3"];
+    let nodeset: NodeSet = hosts.iter().map(Node::new).collect::<Result<_, _>>().unwrap();
+    assert_eq!(nodeset.to_string(), "node[1-3]");
+
+    let hosts = ["gpu2", "node1", "login", "gpu1", "node3", "login"];
+    let mut nodeset: NodeSet = hosts.iter().map(|h| Node::new(h).unwrap()).collect();
+    assert_eq!(nodeset.to_string(), "gpu[1-2],node[1-3/2],login");
+
+    nodeset.extend(vec![Node::new("node2").unwrap(), Node::new("rack1-cpu4").unwrap()]);
+    assert_eq!(nodeset.to_string(), "gpu[1-2],node[1-3],login,rack1-cpu4");
+
+    // A hostname that can not be parsed is an error, not a missing node
+    let hosts = ["node1", "node[5", "node2"];
+    let err = hosts.iter().map(Node::new).collect::<Result<NodeSet, _>>().unwrap_err();
+    assert_eq!(err, NodeErrorType::Regular(ErrorKind::UnbalancedBrackets("[5".to_string(), 4)));
+
+    let empty: NodeSet = Vec::<Node>::new().into_iter().collect();
+    assert!(empty.is_empty());
+
+    let mut nodeset = NodeSet::new("node[1-2]").unwrap();
+    nodeset.try_extend(vec!["node3".to_string(), " gpu1 ".to_string()]).unwrap();
+    assert_eq!(nodeset.to_string(), "node[1-3],gpu1");
+
+    let err = nodeset.try_extend(vec!["node4".to_string(), "node[5".to_string()]).unwrap_err();
+    assert_eq!(err, NodeErrorType::Regular(ErrorKind::UnbalancedBrackets("[5".to_string(), 4)));
+    assert_eq!(nodeset.to_string(), "node[1-3],gpu1");
+}
+
+#[test]
+fn test_nodeset_sorted_expand() {
+    let nodeset = NodeSet::new("node[10,2,1]").unwrap();
+    assert_eq!(nodeset.expand(",").unwrap(), "node10,node2,node1");
+    assert_eq!(nodeset.sorted_expand(",").unwrap(), "node1,node2,node10");
+
+    let nodeset = NodeSet::new("node[2,1]-cpu[10,2],gpu[010-8]").unwrap();
+    assert_eq!(nodeset.sorted_expand(" ").unwrap(), "gpu8 gpu9 gpu10 node1-cpu2 node1-cpu10 node2-cpu2 node2-cpu10");
+}
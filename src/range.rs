@@ -20,25 +20,41 @@
  *  Inc., 59 Temple Place - Suite 330, Boston, MA 02111-1307, USA.
  */
 
+use lazy_static::lazy_static;
+use std::borrow::Cow;
 use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
 use std::error::Error;
 use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::FusedIterator;
 use std::str::FromStr;
+use std::sync::Arc;
 
 #[cfg(test)]
 use std::process::exit; //used for testing
 
+/* Numbers that iter_cow() returns without allocating */
+lazy_static! {
+    static ref SMALL_NUMBERS: Vec<String> = (0..1024).map(|n: u32| n.to_string()).collect();
+}
+
 /// A range is composed of ordered numbers (at least one)
 /// A range may be incremental or decremental. Padding is
 /// taken into account with the number of zeros found at
 /// the beginning of the first number.
 ///
+/// Numbers are stored as u64 so that ranges such as `5000000000-5000000010`
+/// are valid.
+///
 /// Range examples:
 /// * 10-49
 /// * 1-25/2
 /// * 101
 /// * 097-103
 /// * 30-0/4
+/// * a-f or H-A/2 (single letters of the same case)
 ///
 /// Example:
 /// ```rust
@@ -56,14 +72,23 @@ use std::process::exit; //used for testing
  *         is equal to 0 if no padding has to be applied.
  * * `curr` is used to remember the current value when calculating next
  *          number in Range iterator's implementation.
+ * * `done` is true when the iterator went past the last value that an
+ *          u64 can represent (curr can not move further in that case).
+ * * `dashed` is true when the Range has been written with a dash (`5-5`)
+ *            even if start and end are the same.
+ * * `alpha` is true when the Range is made of letters (`a-f`). Letters
+ *           are stored as their ASCII code and displayed as letters.
  */
 #[derive(Debug, Clone)] /* Auto generates Debug and Clone traits */
 pub struct Range {
-    start: u32,
-    end: u32,
-    step: u32,
+    start: u64,
+    end: u64,
+    step: u64,
     pad: usize,
-    curr: u32,
+    curr: u64,
+    done: bool,
+    dashed: bool,
+    alpha: bool,
 }
 
 /// "Guess" the padding that is requested by counting the number
@@ -71,7 +96,7 @@ pub struct Range {
 /// the one generated by getting a new  string from that number.
 pub fn guess_padding(value: &str) -> Result<usize, Box<dyn Error>> {
     let len1 = value.len();
-    let number: u32 = value.parse()?;
+    let number: u64 = value.parse()?;
     let len2 = number.to_string().len();
 
     match len1.cmp(&len2) {
@@ -80,26 +105,55 @@ pub fn guess_padding(value: &str) -> Result<usize, Box<dyn Error>> {
     }
 }
 
-fn range_step_detection(vector: Vec<u32>) -> u32 {
-    let step: u32;
-
-    if vector.len() > 1 {
-        if vector[0] < vector[1] {
-            step = vector[1] - vector[0];
-        } else {
-            step = vector[0] - vector[1];
+/// Parses the step of a Range. The step may be a plain integer (`100`),
+/// use scientific notation (`1e2`) or the `k` suffix (`1k` is `1000`).
+/// Fractional results such as `15e-1` or `1.5k` are refused.
+fn parse_step(step: &str) -> Result<u64, Box<dyn Error>> {
+    let (mantissa, multiplier) = if let Some(mantissa) = step.strip_suffix('k') {
+        (mantissa, 1000)
+    } else if let Some((mantissa, exponent)) = step.split_once('e') {
+        let exponent: u32 = exponent.parse()?;
+        match 10_u64.checked_pow(exponent) {
+            Some(multiplier) => (mantissa, multiplier),
+            None => return Err(format!("step '{step}' is too big").into()),
         }
     } else {
-        step = 1;
+        (step, 1)
+    };
+
+    let mantissa: u64 = mantissa.parse()?;
+    match mantissa.checked_mul(multiplier) {
+        Some(0) => Err(format!("step of a Range can not be 0 ('{step}')").into()),
+        Some(step) => Ok(step),
+        None => Err(format!("step '{step}' is too big").into()),
+    }
+}
+
+// Formats `value` with `pad` digits at least or as a letter when `alpha`
+// is true (see Range::format_value()).
+fn format_value(value: u64, pad: usize, alpha: bool) -> String {
+    match u8::try_from(value) {
+        Ok(letter) if alpha => char::from(letter).to_string(),
+        _ => format!("{value:0pad$}"),
+    }
+}
+
+// Extended Euclid's algorithm: returns (g, x, y) such that
+// a * x + b * y = g where g is the gcd of a and b.
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        (a, 1, 0)
+    } else {
+        let (g, x, y) = extended_gcd(b, a % b);
+        (g, y, x - (a / b) * y)
     }
-    step
 }
 
-/// returns the intersection of two u32 vectors or None
-pub fn vec_u32_intersection(first: Vec<u32>, second: Vec<u32>) -> Option<Vec<u32>> {
-    let mut inter: Vec<u32> = Vec::new();
-    let mut first: Vec<u32> = first;
-    let mut second: Vec<u32> = second;
+/// returns the intersection of two u64 vectors or None
+pub fn values_intersection(first: Vec<u64>, second: Vec<u64>) -> Option<Vec<u64>> {
+    let mut inter: Vec<u64> = Vec::new();
+    let mut first: Vec<u64> = first;
+    let mut second: Vec<u64> = second;
 
     first.sort_unstable();
     second.sort_unstable();
@@ -130,16 +184,57 @@ pub fn vec_u32_intersection(first: Vec<u32>, second: Vec<u32>) -> Option<Vec<u32
     }
 }
 
-// This function needs a non empty sorted Vector of u32.
-// It does fold every numbers in the vector into Ranges
-// that are put in a vector. This vector contains at
-// least one Range.
+/// Former name of `values_intersection()` that works with u64 values.
+#[deprecated(note = "use values_intersection() that has the same behaviour")]
+pub fn vec_u32_intersection(first: Vec<u64>, second: Vec<u64>) -> Option<Vec<u64>> {
+    values_intersection(first, second)
+}
+
+/// returns the values that are in exactly one of the two sorted
+/// and deduplicated u64 vectors (the result is sorted).
+pub(crate) fn values_symmetric_difference(first: &[u64], second: &[u64]) -> Vec<u64> {
+    let mut sym: Vec<u64> = Vec::new();
+
+    let mut i1 = 0;
+    let mut i2 = 0;
+    while i1 < first.len() && i2 < second.len() {
+        match first[i1].cmp(&second[i2]) {
+            Ordering::Equal => {
+                i1 += 1;
+                i2 += 1;
+            }
+            Ordering::Greater => {
+                sym.push(second[i2]);
+                i2 += 1;
+            }
+            Ordering::Less => {
+                sym.push(first[i1]);
+                i1 += 1;
+            }
+        };
+    }
+    sym.extend_from_slice(&first[i1..]);
+    sym.extend_from_slice(&second[i2..]);
+
+    sym
+}
+
+// This function folds every numbers of the vector into Ranges
+// that are put in a vector. The numbers are sorted and
+// duplicates are removed first so that any vector may be
+// given: an empty vector gives an empty vector of Range.
 // pad will be used for all Range in the new Vector
-pub fn fold_vec_u32_in_vec_range(v: Vec<u32>, pad: usize) -> Vec<Range> {
+pub fn fold_values_in_vec_range(v: Vec<u64>, pad: usize) -> Vec<Range> {
     let mut index = 0;
     let mut res: Vec<Range> = Vec::new();
+    let mut v = v;
+
+    v.sort_unstable();
+    v.dedup();
 
-    if v.len() == 1 {
+    if v.is_empty() {
+        res
+    } else if v.len() == 1 {
         // only one value in the vector leads to only one Range with
         // start, end and curr at the same value and step to 1 (by convention)
         let range = Range::new_from_values(v[0], v[0], 1, pad, v[0]);
@@ -187,6 +282,73 @@ pub fn fold_vec_u32_in_vec_range(v: Vec<u32>, pad: usize) -> Vec<Range> {
     }
 }
 
+/// Former name of `fold_values_in_vec_range()` that works with u64 values.
+#[deprecated(note = "use fold_values_in_vec_range() that has the same behaviour")]
+pub fn fold_vec_u32_in_vec_range(v: Vec<u64>, pad: usize) -> Vec<Range> {
+    fold_values_in_vec_range(v, pad)
+}
+
+/// Same as `fold_values_in_vec_range()` but Ranges with a step bigger
+/// than `max_step` are split into single values: `[1, 100]` gives `1,100`
+/// instead of `1-100/99` with a `max_step` lower than 99.
+pub fn fold_values_in_vec_range_max_step(v: Vec<u64>, pad: usize, max_step: u64) -> Vec<Range> {
+    #[rustfmt::skip]
+    let folded = fold_values_in_vec_range(v, pad)
+        .into_iter()
+        .flat_map(|range| {
+            if range.step > max_step {
+                range.generate_values().into_iter().map(|value| Range::new_from_values(value, value, 1, pad, value)).collect()
+            } else {
+                vec![range]
+            }
+        })
+        .collect();
+
+    folded
+}
+
+/// Former name of `fold_values_in_vec_range_max_step()` that works with u64 values.
+#[deprecated(note = "use fold_values_in_vec_range_max_step() that has the same behaviour")]
+pub fn fold_vec_u32_in_vec_range_max_step(v: Vec<u64>, pad: usize, max_step: u64) -> Vec<Range> {
+    fold_values_in_vec_range_max_step(v, pad, max_step)
+}
+
+// Same as `fold_values_in_vec_range()` but the Ranges are made of letters
+// when `alpha` is true. Uppercase and lowercase letters are folded apart
+// as a Range such as `X-a/9` can not be written.
+pub(crate) fn fold_vec_in_vec_range(v: Vec<u64>, pad: usize, alpha: bool) -> Vec<Range> {
+    if !alpha {
+        return fold_values_in_vec_range(v, pad);
+    }
+
+    let (upper, lower): (Vec<u64>, Vec<u64>) = v.into_iter().partition(|v| *v <= b'Z' as u64);
+    #[rustfmt::skip]
+    let letters = [upper, lower]
+        .into_iter()
+        .filter(|values| !values.is_empty())
+        .flat_map(|values| fold_values_in_vec_range(values, 0))
+        .map(|range| Range { alpha, ..range })
+        .collect();
+
+    letters
+}
+
+// Parses the bounds of a Range made of single letters of the same case
+// such as `a-f` or `Z-T`. Returns None when the bounds are not letters.
+fn parse_letters(start: &str, end: &str) -> Option<Result<(u64, u64), Box<dyn Error>>> {
+    let letter = |s: &str| match s.as_bytes() {
+        [c] if c.is_ascii_alphabetic() => Some(*c),
+        _ => None,
+    };
+
+    match (letter(start), letter(end)) {
+        (Some(s), Some(e)) if s.is_ascii_lowercase() == e.is_ascii_lowercase() => Some(Ok((s as u64, e as u64))),
+        (Some(_), Some(_)) => Some(Err(format!("range '{start}-{end}' mixes lowercase and uppercase letters").into())),
+        (Some(_), None) | (None, Some(_)) => Some(Err(format!("range '{start}-{end}' mixes letters and numbers").into())),
+        (None, None) => None,
+    }
+}
+
 impl Range {
     /// True when start range is the same as end ie: this range
     /// has only one number.
@@ -194,6 +356,12 @@ impl Range {
         self.start == self.end
     }
 
+    /// True when the Range has been created from a string using
+    /// a dash such as `5-5` or `1-10` (and not `5`).
+    pub fn was_dashed(&self) -> bool {
+        self.dashed
+    }
+
     /// True if the Range is counting one by one. We won't
     /// use /1 to display the Range as this is the "normal"
     /// case ie we write 1-12 instead of 1-12/1
@@ -204,6 +372,39 @@ impl Range {
     /// Resets the Range to its initial value.
     pub fn reset(&mut self) {
         self.curr = self.start;
+        self.done = false;
+    }
+
+    /// Returns the first value of the Range (`10` for `10-1/3`).
+    pub fn start(&self) -> u64 {
+        self.start
+    }
+
+    /// Returns the end of the Range as written (`1` for `10-1/3`) which
+    /// may not be a value generated by the Range.
+    pub fn end(&self) -> u64 {
+        self.end
+    }
+
+    /// Returns the step of the Range (`1` when none was given).
+    pub fn step(&self) -> u64 {
+        self.step
+    }
+
+    /// True when the Range is made of letters such as `a-f`.
+    pub fn is_alpha(&self) -> bool {
+        self.alpha
+    }
+
+    /// Formats a value of the Range the way the Range displays it
+    /// (with its padding or as a letter).
+    pub(crate) fn format_value(&self, value: u64) -> String {
+        format_value(value, self.pad, self.alpha)
+    }
+
+    /// Raises the padding of the Range to `pad` when it is smaller.
+    pub(crate) fn pad_at_least(&mut self, pad: usize) {
+        self.pad = self.pad.max(pad);
     }
 
     /// Returns the padding that applies to the Range.
@@ -211,11 +412,13 @@ impl Range {
         self.pad
     }
 
-    /// counts the number of values in the Range
-    pub fn len(&self) -> u32 {
+    /// counts the number of values in the Range. The only Range that
+    /// has more values than u64::MAX (`0-18446744073709551615`) is
+    /// counted as having u64::MAX values.
+    pub fn len(&self) -> u64 {
         match self.start.cmp(&self.end) {
-            Ordering::Greater => 1 + ((self.start - self.end) / self.step),
-            Ordering::Less => 1 + ((self.end - self.start) / self.step),
+            Ordering::Greater => ((self.start - self.end) / self.step).saturating_add(1),
+            Ordering::Less => ((self.end - self.start) / self.step).saturating_add(1),
             Ordering::Equal => 1,
         }
     }
@@ -229,7 +432,7 @@ impl Range {
     /// This function is for internal use of the library.
     /// it returns `curr` field of the Range structure that
     /// is used for the Iterator.
-    pub fn get_current(&self) -> u32 {
+    pub fn get_current(&self) -> u64 {
         self.curr
     }
 
@@ -239,106 +442,363 @@ impl Range {
         self.start > self.end
     }
 
+    /// Returns a new Range generating the same values in the opposite
+    /// order: `1-10/4` (1, 5, 9) gives `9-1/4`. The new Range starts from
+    /// its first value.
     pub fn new_range_reversed(&self) -> Range {
+        let last = self.nth_value(self.len() - 1).unwrap_or(self.end);
+
         Range {
-            start: self.end,
+            start: last,
             end: self.start,
             step: self.step,
             pad: self.pad,
-            curr: self.curr,
+            curr: last,
+            done: false,
+            dashed: self.dashed,
+            alpha: self.alpha,
+        }
+    }
+
+    /// Displays the Range with its direction: `10-1/2` when it is in
+    /// descending order and `1-10/2` when it is in ascending order (a
+    /// single value is displayed alone). `Range::new()` gives the same
+    /// Range back from this string, direction included. This is what
+    /// Display does already. Note that `union()` and `intersection()`
+    /// give Ranges in ascending order.
+    pub fn to_string_directional(&self) -> String {
+        self.to_string()
+    }
+
+    /// Returns the smallest and the biggest values really
+    /// generated by the Range (ie: `1-10/4` gives `(1, 9)`).
+    fn bounds(&self) -> (u64, u64) {
+        let last = self.nth_value(self.len() - 1).unwrap_or(self.start);
+
+        (self.start.min(last), self.start.max(last))
+    }
+
+    /// True when all values of the Range are strictly lower
+    /// than `value`.
+    pub fn before(&self, value: u64) -> bool {
+        let (_, max) = self.bounds();
+        max < value
+    }
+
+    /// True when all values of the Range are strictly greater
+    /// than `value`.
+    pub fn after(&self, value: u64) -> bool {
+        let (min, _) = self.bounds();
+        min > value
+    }
+
+    /// Returns a hash of the Range computed from `start`, `end` and
+    /// `step` only. As for equality, padding is not taken into account
+    /// so `1-100/2` and `001-100/2` have the same fingerprint.
+    pub fn fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        (self.start, self.end, self.step).hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Returns the value at position `n` in the Range (the
+    /// first value being at position 0) or None if the Range
+    /// is too short.
+    pub fn nth_value(&self, n: u64) -> Option<u64> {
+        if n >= self.len() {
+            None
+        } else if self.is_reverse_order() {
+            Some(self.start - n * self.step)
+        } else {
+            Some(self.start + n * self.step)
+        }
+    }
+
+    /// Returns the smallest value common to self and the other Range
+    /// along with the step between two consecutive common values (the
+    /// lcm of both steps) or None when they have no common value. No
+    /// value is generated: the common values are the solutions of
+    /// `x = min_a mod step_a` and `x = min_b mod step_b` that lie in
+    /// both Ranges.
+    fn first_common_value(&self, other: &Self) -> Option<(u64, u128)> {
+        let (min_a, max_a) = self.bounds();
+        let (min_b, max_b) = other.bounds();
+        let lo = min_a.max(min_b) as i128;
+        let hi = max_a.min(max_b) as i128;
+        if lo > hi {
+            return None;
+        }
+
+        let (step_a, step_b) = (self.step as i128, other.step as i128);
+        let (g, inverse, _) = extended_gcd(step_a, step_b);
+        let delta = min_b as i128 - min_a as i128;
+        if delta % g != 0 {
+            return None;
+        }
+
+        // min_a + step_a * k is a common value for k = delta / g * inverse (mod step_b / g).
+        // Every operand is reduced below the modulo first so that the products fit in a u128.
+        let modulo = (step_b / g) as u128;
+        let delta = (delta / g).rem_euclid(modulo as i128) as u128;
+        let inverse = inverse.rem_euclid(modulo as i128) as u128;
+        let k = delta * inverse % modulo;
+        let lcm = step_a as u128 * modulo;
+        let mut first = min_a as u128 + step_a as u128 * k;
+        let (lo, hi) = (lo as u128, hi as u128);
+        if first < lo {
+            first = first.checked_add((lo - first).div_ceil(lcm).checked_mul(lcm)?)?;
+        }
+
+        if first <= hi {
+            Some((first as u64, lcm))
+        } else {
+            None
         }
     }
 
-    /// Expands a Range into a vector of u32.
+    /// True when self and the other Range have at least one value in
+    /// common. Nothing is expanded: `1-99/2` and `2-100/2` do not
+    /// overlap whereas `1-99/2` and `2-100/3` do (5, 11, ...).
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.first_common_value(other).is_some()
+    }
+
+    /// True when `value` is one of the values of the Range.
+    pub fn contains(&self, value: u64) -> bool {
+        let (min, max) = self.bounds();
+        value >= min && value <= max && self.start.abs_diff(value).is_multiple_of(self.step)
+    }
+
+    /// Returns the values of self Range that are not in the other
+    /// Range. Order of self Range is kept and no folding is done.
+    pub fn missing_from(&self, other: &Self) -> Vec<u64> {
+        self.generate_values().into_iter().filter(|v| !other.contains(*v)).collect()
+    }
+
+    /// Returns the sub-Range made of the values at positions
+    /// `from` (included) to `to` (excluded). Step, padding and
+    /// order are kept: `10-100/10` sliced with `[2, 5)` gives
+    /// `30-50/10`. Returns None if the slice is empty or out
+    /// of the Range's bounds.
+    pub fn slice(&self, from: u64, to: u64) -> Option<Range> {
+        if from >= to || to > self.len() {
+            return None;
+        }
+
+        let start = self.nth_value(from)?;
+        let end = self.nth_value(to - 1)?;
+
+        let mut slice = Range::new_from_values(start, end, self.step, self.pad, start);
+        slice.alpha = self.alpha;
+        Some(slice)
+    }
+
+    /// Expands a Range into a vector of u64.
     /// Order is taken into account.
-    pub fn generate_vec_u32(&self) -> Vec<u32> {
-        let mut vector: Vec<u32> = Vec::new();
-        let mut index: u32;
+    pub fn generate_values(&self) -> Vec<u64> {
+        let mut vector: Vec<u64> = Vec::new();
+        let mut index: Option<u64> = Some(self.start);
 
         if self.is_reverse_order() {
-            index = self.start;
-            while index >= self.end {
-                vector.push(index);
-                index -= self.step;
+            while let Some(value) = index.filter(|v| *v >= self.end) {
+                vector.push(value);
+                index = value.checked_sub(self.step);
             }
         } else {
-            index = self.start;
-            while index <= self.end {
-                vector.push(index);
-                index += self.step;
+            while let Some(value) = index.filter(|v| *v <= self.end) {
+                vector.push(value);
+                index = value.checked_add(self.step);
             }
         }
 
         vector
     }
 
+    /// Former name of `generate_values()` that returns u64 values.
+    #[deprecated(note = "use generate_values() that has the same behaviour")]
+    pub fn generate_vec_u32(&self) -> Vec<u64> {
+        self.generate_values()
+    }
+
+    /// Same as `generate_values()` but fills `buf` (that is cleared
+    /// first) instead of allocating a new vector. The capacity of `buf`
+    /// is reused between calls.
+    pub fn all_values_collect_into(&self, buf: &mut Vec<u64>) {
+        buf.clear();
+        buf.reserve(self.len() as usize);
+        buf.extend((0..self.len()).filter_map(|n| self.nth_value(n)));
+    }
+
+    /// Returns an iterator over the values of the Range from the first
+    /// one. Unlike iterating the Range itself self is not modified.
+    pub fn iter(&self) -> RangeIter<'_> {
+        RangeIter {
+            range: self,
+            n: 0,
+        }
+    }
+
+    /// Iterates over the values of the Range as `iter()` would do without
+    /// modifying self. Values below 1024 that do not need padding are
+    /// borrowed from a static table instead of being allocated.
+    pub fn iter_cow(&self) -> impl Iterator<Item = Cow<'static, str>> {
+        let mut range = self.clone();
+        let (pad, alpha) = (self.pad, self.alpha);
+
+        range.reset();
+        std::iter::from_fn(move || range.get_next()).map(move |v| match SMALL_NUMBERS.get(v as usize) {
+            Some(s) if s.len() >= pad && !alpha => Cow::Borrowed(s.as_str()),
+            _ => Cow::Owned(format_value(v, pad, alpha)),
+        })
+    }
+
     /// Returns a new Range that is the union with the other one
     /// Order (reverse or not) is not kept in the new Range
     /// and is always forward
     pub fn union(&self, other: &Self) -> Vec<Range> {
-        let mut first: Vec<u32> = self.generate_vec_u32();
-        let mut second: Vec<u32> = other.generate_vec_u32();
+        let mut first: Vec<u64> = self.generate_values();
+        let mut second: Vec<u64> = other.generate_values();
 
         let pad = self.pad.max(other.pad);
         first.append(&mut second);
         first.sort_unstable();
         first.dedup();
-        fold_vec_u32_in_vec_range(first, pad)
+        fold_vec_in_vec_range(first, pad, self.alpha)
     }
 
-    /// Returns a new Range that is the intersection or None.
-    /// Order (reverse or not) is not kept in the new Range
-    /// and is always forward
-    /// Step detection is always possible because we are in
-    /// an intersection of two ranges with stable step propriety
-    pub fn intersection(&self, other: &Self) -> Option<Range> {
-        let mut first: Vec<u32> = self.generate_vec_u32();
-        let mut second: Vec<u32> = other.generate_vec_u32();
+    /// Returns the Ranges made of the values of self Range that are
+    /// not in the other one: `1-20` minus `5-10` gives `1-4` and `11-20`.
+    /// Order (reverse or not) is not kept in the new Ranges
+    /// and is always forward. The vector is empty when all values of
+    /// self are in other.
+    pub fn difference(&self, other: &Self) -> Vec<Range> {
+        let mut first: Vec<u64> = self.generate_values();
+        let mut second: Vec<u64> = other.generate_values();
+
+        first.sort_unstable();
+        second.sort_unstable();
+        first.retain(|v| second.binary_search(v).is_err());
+
+        if first.is_empty() {
+            Vec::new()
+        } else {
+            let pad = self.pad.max(other.pad);
+            fold_vec_in_vec_range(first, pad, self.alpha)
+        }
+    }
+
+    /// Returns the values between `lo` and `hi` (both included) that are
+    /// not values of the Range, folded: `5-8` within `1-10` gives `1-4`
+    /// and `9-10`. The result is empty when `lo` is bigger than `hi`.
+    pub fn complement_within(&self, lo: u64, hi: u64) -> Vec<Range> {
+        if lo > hi {
+            return Vec::new();
+        }
+
+        let universe = Range {
+            alpha: self.alpha,
+            ..Range::new_from_values(lo, hi, 1, self.pad, lo)
+        };
+        universe.difference(self)
+    }
+
+    /// Returns the Ranges made of the values that are in exactly one of
+    /// self and other: `1-10` and `5-15` gives `1-4,11-15`. The result
+    /// is always forward and is empty when both Ranges have the same values.
+    pub fn symmetric_difference(&self, other: &Self) -> Vec<Range> {
+        let mut first: Vec<u64> = self.generate_values();
+        let mut second: Vec<u64> = other.generate_values();
 
         first.sort_unstable();
         second.sort_unstable();
 
-        match vec_u32_intersection(first, second) {
-            Some(inter) => {
-                let start = inter[0];
-                let last = inter.len() - 1;
-                let end = inter[last];
-                let pad = self.pad.max(other.pad);
-                let step = range_step_detection(inter);
-
-                Some(Range {
-                    start,
-                    end,
-                    pad,
-                    curr: start,
-                    step,
-                })
+        let sym = values_symmetric_difference(&first, &second);
+        if sym.is_empty() {
+            Vec::new()
+        } else {
+            let pad = self.pad.max(other.pad);
+            fold_vec_in_vec_range(sym, pad, self.alpha)
+        }
+    }
+
+    /// Returns the Ranges made of the values that are both in self and in
+    /// the other Range. Order (reverse or not) is not kept in the new
+    /// Ranges and is always forward. The common values of two Ranges are
+    /// evenly spaced (every lcm of both steps) so that the vector holds
+    /// one Range computed without generating any value. The vector is
+    /// empty when there is no common value.
+    pub fn intersection(&self, other: &Self) -> Vec<Range> {
+        let (first, lcm) = match self.first_common_value(other) {
+            Some(common) => common,
+            None => return Vec::new(),
+        };
+
+        let (_, max_a) = self.bounds();
+        let (_, max_b) = other.bounds();
+        let count = (max_a.min(max_b) - first) as u128 / lcm;
+        let last = first + (count * lcm) as u64;
+        // By convention a Range with only one value has a step of 1
+        let step = if count == 0 {
+            1
+        } else {
+            lcm as u64
+        };
+
+        vec![Range {
+            alpha: self.alpha,
+            ..Range::new_from_values(first, last, step, self.pad.max(other.pad), first)
+        }]
+    }
+
+    /// Counts the values that the iterator has not generated yet.
+    fn remaining(&self) -> u64 {
+        let curr = self.curr;
+
+        if self.done {
+            0
+        } else if self.is_reverse_order() {
+            if curr < self.end || curr > self.start {
+                0
+            } else {
+                1 + (curr - self.end) / self.step
             }
-            None => None,
+        } else if curr > self.end || curr < self.start {
+            0
+        } else {
+            1 + (self.end - curr) / self.step
         }
     }
 
-    /// Returns the next value as an `Option<u32>`.
+    /// Returns the next value as an `Option<u64>`.
     /// It returns None when there is no next value to
     /// get. Note that Range implements Iterator trait
     /// that you may use in normal cases.
-    pub fn get_next(&mut self) -> Option<u32> {
+    pub fn get_next(&mut self) -> Option<u64> {
         let curr = self.curr;
 
-        if self.is_reverse_order() {
+        /* When the next value can not be represented by an u64 the */
+        /* iteration is marked as done as curr can not move further */
+        if self.done {
+            return None;
+        }
+
+        let next = if self.is_reverse_order() {
             /* going backward here */
-            if curr < self.end {
+            if curr < self.end || curr > self.start {
                 return None;
-            } else {
-                self.curr = curr - self.step;
             }
+            curr.checked_sub(self.step)
         } else {
             /* going forward here */
-            if curr > self.end {
+            if curr > self.end || curr < self.start {
                 return None;
-            } else {
-                self.curr = curr + self.step;
             }
+            curr.checked_add(self.step)
+        };
+
+        match next {
+            Some(next) => self.curr = next,
+            None => self.done = true,
         }
         Some(curr)
     }
@@ -346,31 +806,100 @@ impl Range {
     /// Creates a new Range directly from the values
     /// that defines it: `start-end/step`
     /// pad is the minimal number of number needed: `2` with `Pad = 3` is `002`
-    pub fn new_from_values(start: u32, end: u32, step: u32, pad: usize, curr: u32) -> Range {
+    /// step must not be 0 (iterating such a Range would never end).
+    pub fn new_from_values(start: u64, end: u64, step: u64, pad: usize, curr: u64) -> Range {
+        debug_assert!(step != 0, "step of a Range can not be 0");
         Range {
             start,
             end,
             step,
             pad,
             curr,
+            done: false,
+            dashed: false,
+            alpha: false,
+        }
+    }
+
+    /// Creates a new Range from its first value, the number of values
+    /// it has and the step between them: `from_start_len(2, 4, 2, 0)` is
+    /// `2-8/2`. `len` and `step` must be at least 1.
+    pub fn from_start_len(start: u64, len: u64, step: u64, pad: usize) -> Result<Range, Box<dyn Error>> {
+        if len == 0 {
+            return Err("a Range must have at least one value".into());
+        }
+        if step == 0 {
+            return Err("step of a Range can not be 0".into());
+        }
+
+        match (len - 1).checked_mul(step).and_then(|span| span.checked_add(start)) {
+            Some(end) => Ok(Range::new_from_values(start, end, step, pad, start)),
+            None => Err("end of the Range is too big".into()),
+        }
+    }
+
+    /// Returns a new Range with the same bounds, padding and order as
+    /// self but generating values every `step`: `1-100` with a step of
+    /// 25 gives `1-100/25` (1, 26, 51 and 76). The new Range starts from
+    /// its first value.
+    pub fn with_step(&self, step: u64) -> Result<Range, Box<dyn Error>> {
+        if step == 0 {
+            return Err("step of a Range can not be 0".into());
         }
+
+        Ok(Range {
+            step,
+            curr: self.start,
+            done: false,
+            ..self.clone()
+        })
     }
 
     /// Creates a new Range with an &str like `1-5/2` or `1` or `9-15`
     /// it may even be in reverse mode such as `15-9`. Padding is
-    /// guessed in either mode.
+    /// guessed in either mode. `:` may be used instead of `-` (`1:10`)
+    /// but both can not be mixed.
     pub fn new(strange: &str) -> Result<Range, Box<dyn Error>> {
         /* Try to figure out if we have a base/step formatted range */
+        /* Spaces around '-' and '/' are allowed: `1 - 10 / 2`       */
+        let strange = strange.trim();
+        if strange.matches('/').count() > 1 {
+            return Err(format!("range '{strange}' has more than one step separator").into());
+        }
         let (base, step) = match strange.split_once('/') {
-            Some((base, step)) => (base, step.parse()?),
+            Some((base, step)) => (base.trim(), parse_step(step.trim())?),
             None => (strange, 1),
         };
 
         /* Base is formatted like start-end or with only one number */
-        let (start_str, end_str) = match base.split_once('-') {
-            Some((start, end)) => (start, end),
-            None => (base, base),
+        /* `start:end` is also accepted if no '-' is used            */
+        if base.contains('-') && base.contains(':') {
+            return Err(format!("range '{strange}' mixes '-' and ':' separators").into());
+        }
+        let separator = if base.contains(':') {
+            ':'
+        } else {
+            '-'
         };
+        let (start_str, end_str, dashed) = match base.split_once(separator) {
+            Some((start, end)) => (start.trim(), end.trim(), true),
+            None => (base, base, false),
+        };
+
+        /* Letters are never padded */
+        if let Some(letters) = parse_letters(start_str, end_str) {
+            let (start, end) = letters?;
+            return Ok(Range {
+                start,
+                end,
+                step,
+                pad: 0,
+                curr: start,
+                done: false,
+                dashed,
+                alpha: true,
+            });
+        }
 
         /* Determining if we need padding, if start begins with zeros    */
         /* for example 001 needs padding where as 189 doesn't            */
@@ -393,21 +922,121 @@ impl Range {
             step,
             pad,
             curr,
+            done: false,
+            dashed,
+            alpha: false,
         })
     }
 }
 
+/// Deduplicates Ranges: interning two Ranges that have the same start,
+/// end, step and padding returns the same shared Range. This saves memory
+/// when a lot of nodesets use the same ranges (`cpu[1-64]` for instance).
+///
+/// Example:
+/// ```rust
+/// use nodeset::{Range, RangeInterner};
+/// use std::sync::Arc;
+/// let mut interner = RangeInterner::new();
+/// let a = interner.intern(Range::new("1-64").unwrap());
+/// let b = interner.intern(Range::new("1-64").unwrap());
+/// assert!(Arc::ptr_eq(&a, &b));
+/// ```
+#[derive(Debug, Default)]
+pub struct RangeInterner {
+    ranges: HashMap<(u64, u64, u64, usize, bool), Arc<Range>>,
+}
+
+impl RangeInterner {
+    pub fn new() -> RangeInterner {
+        RangeInterner::default()
+    }
+
+    /// Returns the shared Range equal to `range` (padding included).
+    /// The shared Range is created from `range` if it does not exist yet.
+    pub fn intern(&mut self, range: Range) -> Arc<Range> {
+        let key = (range.start, range.end, range.step, range.pad, range.alpha);
+        let shared = self.ranges.entry(key).or_insert_with(|| {
+            let mut range = range;
+            range.reset();
+            Arc::new(range)
+        });
+        Arc::clone(shared)
+    }
+
+    /// Number of distinct Ranges interned
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    /// Tells whether a Range has already been interned or not.
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+}
+
 /// Range iterator returns an already padded String.
 impl Iterator for Range {
     type Item = String;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let curr = match self.get_next() {
-            Some(value) => value,
-            None => return None,
-        };
-        let pad = self.pad;
-        Some(format!("{curr:0pad$}"))
+        let curr = self.get_next()?;
+        Some(self.format_value(curr))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match usize::try_from(self.remaining()) {
+            Ok(remaining) => (remaining, Some(remaining)),
+            Err(_) => (usize::MAX, None),
+        }
+    }
+
+    /// Counts the remaining values without generating them.
+    fn count(self) -> usize {
+        usize::try_from(self.remaining()).unwrap_or(usize::MAX)
+    }
+}
+
+impl ExactSizeIterator for Range {}
+
+/// Once exhausted a Range keeps returning None until it is `reset()`.
+impl FusedIterator for Range {}
+
+/// Iterator over the values of a borrowed Range with its own cursor:
+/// the Range is not modified and may be iterated again.
+#[derive(Debug, Clone)]
+pub struct RangeIter<'a> {
+    range: &'a Range,
+    n: u64,
+}
+
+impl Iterator for RangeIter<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = self.range.nth_value(self.n)?;
+        self.n += 1;
+        Some(self.range.format_value(value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match usize::try_from(self.range.len().saturating_sub(self.n)) {
+            Ok(remaining) => (remaining, Some(remaining)),
+            Err(_) => (usize::MAX, None),
+        }
+    }
+}
+
+impl FusedIterator for RangeIter<'_> {}
+
+/// Iterates over all the values of the Range from the first one
+/// whatever the state of its own iterator is.
+impl<'a> IntoIterator for &'a Range {
+    type Item = String;
+    type IntoIter = RangeIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
     }
 }
 
@@ -421,14 +1050,13 @@ impl FromStr for Range {
 }
 
 /// Display trait for Range. It will display the range in a folded way: 01-18/3.
+/// Alternate form (`{:#}`) keeps the dash of Ranges written as `5-5`.
 impl fmt::Display for Range {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let pad = self.pad;
-
-        let start_end_str: String = if self.start != self.end {
-            format!("{:0pad$}-{:0pad$}", self.start, self.end)
+        let start_end_str: String = if self.start != self.end || (f.alternate() && self.dashed) {
+            format!("{}-{}", self.format_value(self.start), self.format_value(self.end))
         } else {
-            format!("{:0pad$}", self.start)
+            self.format_value(self.start)
         };
 
         let to_display: String = if self.step != 1 {
@@ -442,17 +1070,47 @@ impl fmt::Display for Range {
 }
 
 /// PartialEq trait for Range to know if a range is equal or not
-/// to another range.
+/// to another range. `a-c` is not equal to `97-99`.
 /// padding is not taken into account ie `1-100/2` equals `001-100/2`
 /// curr is not taken into account the range is the same anywhere
 /// the iterator may be located
 impl PartialEq for Range {
     fn eq(&self, other: &Self) -> bool {
-        self.start == other.start && self.end == other.end && self.step == other.step
+        self.start == other.start && self.end == other.end && self.step == other.step && self.alpha == other.alpha
         // && self.pad == other.pad
     }
 }
 
+impl Eq for Range {}
+
+/// Hash trait for Range. As for equality padding and iterator's
+/// position are not taken into account.
+impl Hash for Range {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.start.hash(state);
+        self.end.hash(state);
+        self.step.hash(state);
+        self.alpha.hash(state);
+    }
+}
+
+/// Numbers come before letters, then Ranges are ordered by `start`, then
+/// `end`, then `step` (padding and curr are not taken into account as in
+/// PartialEq). As Range is also
+/// an Iterator, use `Ord::cmp(&a, &b)` rather than `a.cmp(&b)` that
+/// would compare the generated values.
+impl Ord for Range {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.alpha, self.start, self.end, self.step).cmp(&(other.alpha, other.start, other.end, other.step))
+    }
+}
+
+impl PartialOrd for Range {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(Ord::cmp(self, other))
+    }
+}
+
 /*********************************** Tests ***********************************/
 
 #[cfg(test)] /* Helper function for testing */
@@ -481,7 +1139,10 @@ fn testing_creating_range() {
             end: 10,
             step: 1,
             pad: 0,
-            curr: 0
+            curr: 0,
+            done: false,
+            dashed: false,
+            alpha: false,
         }
     );
 
@@ -493,7 +1154,10 @@ fn testing_creating_range() {
             end: 1,
             step: 1,
             pad: 0,
-            curr: 0
+            curr: 0,
+            done: false,
+            dashed: false,
+            alpha: false,
         }
     );
 
@@ -505,7 +1169,10 @@ fn testing_creating_range() {
             end: 10,
             step: 2,
             pad: 0,
-            curr: 0
+            curr: 0,
+            done: false,
+            dashed: false,
+            alpha: false,
         }
     );
 
@@ -517,7 +1184,10 @@ fn testing_creating_range() {
             end: 1,
             step: 3,
             pad: 0,
-            curr: 0
+            curr: 0,
+            done: false,
+            dashed: false,
+            alpha: false,
         }
     );
 }
@@ -538,6 +1208,12 @@ fn testing_range_values() {
 
     let value = get_range_values_from_str("42-38");
     assert_eq!(value, vec!["42", "41", "40", "39", "38"]);
+
+    let value = get_range_values_from_str("9-1/2");
+    assert_eq!(value, vec!["9", "7", "5", "3", "1"]);
+
+    let value = get_range_values_from_str("4294967290-4294967295/3");
+    assert_eq!(value, vec!["4294967290", "4294967293"]);
 }
 
 #[test]
@@ -550,13 +1226,16 @@ fn testing_range_intersection() {
     // 5 9 13
     assert_eq!(
         inter,
-        Some(Range {
+        vec![Range {
             start: 5,
             end: 13,
             step: 4,
             pad: 0,
-            curr: 5
-        })
+            curr: 5,
+            done: false,
+            dashed: false,
+            alpha: false,
+        }]
     );
 
     let range_a: Range = "38-44".parse().unwrap();
@@ -567,13 +1246,16 @@ fn testing_range_intersection() {
     // 38 39 40
     assert_eq!(
         inter,
-        Some(Range {
+        vec![Range {
             start: 38,
             end: 40,
             step: 1,
             pad: 0,
-            curr: 38
-        })
+            curr: 38,
+            done: false,
+            dashed: false,
+            alpha: false,
+        }]
     );
 
     let range_a: Range = "1-20/2".parse().unwrap();
@@ -581,7 +1263,7 @@ fn testing_range_intersection() {
     let range_b: Range = "2-20/2".parse().unwrap();
     // 2 4 6 8 9 ...
     let inter = range_a.intersection(&range_b);
-    assert_eq!(inter, None);
+    assert!(inter.is_empty());
 
     let range_a: Range = "2-20/2".parse().unwrap();
     // 2 4 6 ... 16 18 20
@@ -591,13 +1273,16 @@ fn testing_range_intersection() {
     // 20
     assert_eq!(
         inter,
-        Some(Range {
+        vec![Range {
             start: 20,
             end: 20,
             step: 1,
             pad: 0,
-            curr: 20
-        })
+            curr: 20,
+            done: false,
+            dashed: false,
+            alpha: false,
+        }]
     );
 
     let range_a: Range = "02-40/2".parse().unwrap();
@@ -608,13 +1293,16 @@ fn testing_range_intersection() {
     // 24 30 36
     assert_eq!(
         inter,
-        Some(Range {
+        vec![Range {
             start: 24,
             end: 36,
             step: 6,
             pad: 2,
-            curr: 20
-        })
+            curr: 24,
+            done: false,
+            dashed: false,
+            alpha: false,
+        }]
     );
 }
 
@@ -633,7 +1321,10 @@ fn testing_range_union() {
             end: 19,
             step: 2,
             pad: 0,
-            curr: 1
+            curr: 1,
+            done: false,
+            dashed: false,
+            alpha: false,
         },]
     );
 
@@ -651,14 +1342,20 @@ fn testing_range_union() {
                 end: 44,
                 step: 1,
                 pad: 0,
-                curr: 38
+                curr: 38,
+                done: false,
+                dashed: false,
+                alpha: false,
             },
             Range {
                 start: 50,
                 end: 56,
                 step: 1,
                 pad: 0,
-                curr: 50
+                curr: 50,
+                done: false,
+                dashed: false,
+                alpha: false,
             },
         ]
     );
@@ -675,7 +1372,10 @@ fn testing_range_union() {
             end: 20,
             step: 1,
             pad: 0,
-            curr: 1
+            curr: 1,
+            done: false,
+            dashed: false,
+            alpha: false,
         },]
     );
 
@@ -692,7 +1392,10 @@ fn testing_range_union() {
             end: 40,
             step: 2,
             pad: 0,
-            curr: 1
+            curr: 2,
+            done: false,
+            dashed: false,
+            alpha: false,
         },]
     );
 
@@ -713,64 +1416,695 @@ fn testing_range_union() {
                 end: 20,
                 step: 2,
                 pad: 2,
-                curr: 1
+                curr: 2,
+                done: false,
+                dashed: false,
+                alpha: false,
             },
             Range {
                 start: 21,
                 end: 22,
                 step: 1,
                 pad: 2,
-                curr: 21
+                curr: 21,
+                done: false,
+                dashed: false,
+                alpha: false,
             },
             Range {
                 start: 24,
                 end: 26,
                 step: 2,
                 pad: 2,
-                curr: 24
+                curr: 24,
+                done: false,
+                dashed: false,
+                alpha: false,
             },
             Range {
                 start: 27,
                 end: 28,
                 step: 1,
                 pad: 2,
-                curr: 27
+                curr: 27,
+                done: false,
+                dashed: false,
+                alpha: false,
             },
             Range {
                 start: 30,
                 end: 32,
                 step: 2,
                 pad: 2,
-                curr: 30
+                curr: 30,
+                done: false,
+                dashed: false,
+                alpha: false,
             },
             Range {
                 start: 33,
                 end: 34,
                 step: 1,
                 pad: 2,
-                curr: 33
+                curr: 33,
+                done: false,
+                dashed: false,
+                alpha: false,
             },
             Range {
                 start: 36,
                 end: 38,
                 step: 2,
                 pad: 2,
-                curr: 36
+                curr: 36,
+                done: false,
+                dashed: false,
+                alpha: false,
             },
             Range {
                 start: 39,
                 end: 40,
                 step: 1,
                 pad: 2,
-                curr: 39
+                curr: 39,
+                done: false,
+                dashed: false,
+                alpha: false,
             },
             Range {
                 start: 42,
                 end: 60,
                 step: 3,
                 pad: 2,
-                curr: 42
+                curr: 42,
+                done: false,
+                dashed: false,
+                alpha: false,
             }
         ]
     );
 }
+
+#[test]
+fn testing_range_before_after() {
+    let range: Range = "1-10".parse().unwrap();
+    assert!(!range.before(0));
+    assert!(range.after(0));
+    assert!(!range.before(5));
+    assert!(!range.after(5));
+    assert!(range.before(20));
+    assert!(!range.after(20));
+
+    // 1 5 9 : 9 is the biggest value not 10
+    let range: Range = "1-10/4".parse().unwrap();
+    assert!(range.before(10));
+
+    // 10 7 4 1
+    let range: Range = "10-1/3".parse().unwrap();
+    assert!(range.after(0));
+    assert!(!range.after(1));
+    assert!(range.before(11));
+}
+
+#[test]
+fn testing_range_slice() {
+    let range: Range = "10-100/10".parse().unwrap();
+    assert_eq!(range.nth_value(0), Some(10));
+    assert_eq!(range.nth_value(9), Some(100));
+    assert_eq!(range.nth_value(10), None);
+    assert_eq!(range.slice(2, 5), Some("30-50/10".parse().unwrap()));
+    assert_eq!(range.slice(0, 10), Some(range.clone()));
+    assert_eq!(range.slice(3, 3), None);
+    assert_eq!(range.slice(5, 11), None);
+
+    let range: Range = "20-01/3".parse().unwrap();
+    // 20 17 14 11 08 05 02
+    let slice = range.slice(1, 4).unwrap();
+    assert_eq!(slice, "17-11/3".parse().unwrap());
+    assert_eq!(slice.collect::<Vec<String>>(), vec!["17", "14", "11"]);
+
+    let range: Range = "001-100".parse().unwrap();
+    let slice = range.slice(8, 11).unwrap();
+    assert_eq!(slice.get_pad(), 3);
+    assert_eq!(slice.collect::<Vec<String>>(), vec!["009", "010", "011"]);
+}
+
+#[test]
+fn testing_range_missing_from() {
+    let range_a: Range = "1-10".parse().unwrap();
+    let range_b: Range = "5-8".parse().unwrap();
+    assert_eq!(range_a.missing_from(&range_b), vec![1, 2, 3, 4, 9, 10]);
+    assert_eq!(range_b.missing_from(&range_a), Vec::<u64>::new());
+
+    let range_a: Range = "10-1".parse().unwrap();
+    let range_b: Range = "0-20/2".parse().unwrap();
+    assert_eq!(range_a.missing_from(&range_b), vec![9, 7, 5, 3, 1]);
+
+    assert!(range_b.contains(20));
+    assert!(!range_b.contains(22));
+    assert!(!range_b.contains(7));
+}
+
+#[test]
+fn testing_range_fingerprint() {
+    let range_a: Range = "1-100/2".parse().unwrap();
+    let range_b: Range = "001-100/2".parse().unwrap();
+    let range_c: Range = "1-100".parse().unwrap();
+    let range_d: Range = "100-1/2".parse().unwrap();
+    assert_eq!(range_a.fingerprint(), range_b.fingerprint());
+    assert_ne!(range_a.fingerprint(), range_c.fingerprint());
+    assert_ne!(range_a.fingerprint(), range_d.fingerprint());
+}
+
+#[test]
+fn testing_range_step_notation() {
+    let value = get_range_values_from_str("1-1000/1e2");
+    assert_eq!(value, vec!["1", "101", "201", "301", "401", "501", "601", "701", "801", "901"]);
+
+    let value = get_range_values_from_str("1-3000/1k");
+    assert_eq!(value, vec!["1", "1001", "2001"]);
+
+    let range: Range = "1-1000/25e1".parse().unwrap();
+    assert_eq!(range, "1-1000/250".parse().unwrap());
+
+    assert!(Range::new("1-1000/1.5k").is_err());
+    assert!(Range::new("1-1000/15e-1").is_err());
+    assert!(Range::new("1-1000/1e2k").is_err());
+    assert!(Range::new("1-1000/1e20").is_err());
+    assert!(Range::new("1-1000/e2").is_err());
+}
+
+#[test]
+fn testing_range_was_dashed() {
+    let range_a: Range = "5".parse().unwrap();
+    let range_b: Range = "5-5".parse().unwrap();
+    assert!(!range_a.was_dashed());
+    assert!(range_b.was_dashed());
+    assert!(range_b.start_is_end());
+    assert_eq!(range_a, range_b);
+    assert_eq!(format!("{range_a}"), "5");
+    assert_eq!(format!("{range_b}"), "5");
+    assert_eq!(format!("{range_a:#}"), "5");
+    assert_eq!(format!("{range_b:#}"), "5-5");
+    assert_eq!(format!("{:#}", Range::new("05-05/2").unwrap()), "05-05/2");
+}
+
+#[test]
+fn testing_range_from_start_len() {
+    let range = Range::from_start_len(2, 4, 2, 0).unwrap();
+    assert_eq!(range, "2-8/2".parse().unwrap());
+    assert_eq!(range.len(), 4);
+
+    let range = Range::from_start_len(7, 1, 3, 3).unwrap();
+    assert_eq!(range.collect::<Vec<String>>(), vec!["007"]);
+
+    assert!(Range::from_start_len(2, 0, 2, 0).is_err());
+    assert!(Range::from_start_len(2, 4, 0, 0).is_err());
+    assert!(Range::from_start_len(u64::MAX, 2, 1, 0).is_err());
+}
+
+#[test]
+fn testing_range_with_spaces() {
+    let range: Range = "1 - 5".parse().unwrap();
+    assert_eq!(range, "1-5".parse().unwrap());
+
+    let range: Range = "01-10 / 2".parse().unwrap();
+    assert_eq!(range, "1-10/2".parse().unwrap());
+    assert_eq!(range.get_pad(), 2);
+
+    let range: Range = " 10 -1 /3 ".parse().unwrap();
+    assert_eq!(range, "10-1/3".parse().unwrap());
+
+    assert!(Range::new("1 0").is_err());
+    assert!(Range::new("1-1 0").is_err());
+    assert!(Range::new("1-10/1 0").is_err());
+}
+
+#[test]
+fn testing_range_all_values_collect_into() {
+    let mut buf: Vec<u64> = Vec::new();
+
+    let range: Range = "1-100".parse().unwrap();
+    range.all_values_collect_into(&mut buf);
+    assert_eq!(buf, range.generate_values());
+    let capacity = buf.capacity();
+
+    let range: Range = "10-1/3".parse().unwrap();
+    range.all_values_collect_into(&mut buf);
+    assert_eq!(buf, vec![10, 7, 4, 1]);
+    assert_eq!(buf.capacity(), capacity);
+}
+
+#[test]
+fn testing_range_difference() {
+    let range_a: Range = "1-20".parse().unwrap();
+    let range_b: Range = "5-10".parse().unwrap();
+    assert_eq!(range_a.difference(&range_b), vec!["1-4".parse().unwrap(), "11-20".parse::<Range>().unwrap()]);
+    assert_eq!(range_b.difference(&range_a), vec![]);
+    assert_eq!(range_a.difference(&range_a), vec![]);
+
+    // reverse ordered ranges
+    let range_a: Range = "20-1".parse().unwrap();
+    let range_b: Range = "10-5".parse().unwrap();
+    assert_eq!(range_a.difference(&range_b), vec!["1-4".parse().unwrap(), "11-20".parse::<Range>().unwrap()]);
+
+    let range_a: Range = "1-14/4".parse().unwrap();
+    // 1 5 9 13
+    let range_b: Range = "19-3/2".parse().unwrap();
+    // 19 17 15 13 11 9 7 5 3
+    assert_eq!(range_a.difference(&range_b), vec!["1".parse::<Range>().unwrap()]);
+
+    // larger padding is kept
+    let range_a: Range = "001-10".parse().unwrap();
+    let range_b: Range = "3-10".parse().unwrap();
+    let diff = range_a.difference(&range_b);
+    assert_eq!(diff, vec!["1-2".parse::<Range>().unwrap()]);
+    assert_eq!(diff[0].get_pad(), 3);
+}
+
+#[test]
+fn testing_range_multiple_steps() {
+    let err = Range::new("1-10/2/3").unwrap_err();
+    assert_eq!(err.to_string(), "range '1-10/2/3' has more than one step separator");
+    assert!(Range::new("1-10//2").is_err());
+}
+
+#[test]
+fn testing_range_step_zero() {
+    let err = Range::new("1-10/0").unwrap_err();
+    assert_eq!(err.to_string(), "step of a Range can not be 0 ('0')");
+    assert!(Range::new("1-10/0k").is_err());
+    assert!(Range::new("1-10/0e2").is_err());
+}
+
+#[test]
+fn testing_range_len() {
+    let range: Range = "1-10/3".parse().unwrap();
+    // 1 4 7 10
+    assert_eq!(range.len(), 4);
+    let range: Range = "1-10/4".parse().unwrap();
+    // 1 5 9
+    assert_eq!(range.len(), 3);
+    let range: Range = "10-1/3".parse().unwrap();
+    // 10 7 4 1
+    assert_eq!(range.len(), 4);
+
+    let ranges = ["1-10", "1-10/3", "1-10/4", "1-10/9", "1-10/10", "10-1/3", "10-1/4", "10-0/3", "9-1/2", "100-0/7", "0-100/7", "5", "5-5/3", "001-100/33"];
+    for r in ranges {
+        let range: Range = r.parse().unwrap();
+        assert_eq!(range.len() as usize, range.clone().count(), "len() and iterator mismatch for {r}");
+        assert_eq!(range.len() as usize, range.generate_values().len(), "len() and generate_values() mismatch for {r}");
+    }
+}
+
+#[test]
+fn testing_range_large_numbers() {
+    let value = get_range_values_from_str("5000000000-5000000010/5");
+    assert_eq!(value, vec!["5000000000", "5000000005", "5000000010"]);
+
+    let range_a: Range = "5000000000-5000000010".parse().unwrap();
+    let range_b: Range = "5000000008-5000000020".parse().unwrap();
+    assert_eq!(range_a.len(), 11);
+    assert_eq!(range_a.to_string(), "5000000000-5000000010".to_string());
+    assert_eq!(range_a.intersection(&range_b), vec!["5000000008-5000000010".parse::<Range>().unwrap()]);
+    assert_eq!(range_a.union(&range_b), vec!["5000000000-5000000020".parse::<Range>().unwrap()]);
+
+    let value = get_range_values_from_str("18446744073709551610-18446744073709551615/4");
+    assert_eq!(value, vec!["18446744073709551610", "18446744073709551614"]);
+}
+
+#[test]
+fn testing_range_interner() {
+    let mut interner = RangeInterner::new();
+    assert!(interner.is_empty());
+
+    let a = interner.intern("1-64".parse().unwrap());
+    let b = interner.intern("1-64".parse().unwrap());
+    let c = interner.intern("01-64".parse().unwrap());
+    let d = interner.intern("1-64/2".parse().unwrap());
+    assert!(Arc::ptr_eq(&a, &b));
+    assert!(!Arc::ptr_eq(&a, &c));
+    assert!(!Arc::ptr_eq(&a, &d));
+    assert_eq!(interner.len(), 3);
+}
+
+#[test]
+fn testing_range_accessors() {
+    let range = Range::new("10-1/3").unwrap();
+    assert_eq!((range.start(), range.end(), range.step()), (10, 1, 3));
+
+    let range = Range::new("007").unwrap();
+    assert_eq!((range.start(), range.end(), range.step(), range.get_pad()), (7, 7, 1, 3));
+}
+
+#[test]
+fn testing_range_ordering() {
+    let mut ranges: Vec<Range> = ["10-20", "1-5", "1-5/2", "3"].iter().map(|r| r.parse().unwrap()).collect();
+    ranges.sort();
+    assert_eq!(ranges.iter().map(|r| r.to_string()).collect::<Vec<String>>(), vec!["1-5", "1-5/2", "3", "10-20"]);
+
+    let a = Range::new("01-10").unwrap();
+    let b = Range::new("1-10").unwrap();
+    assert_eq!(Ord::cmp(&a, &b), Ordering::Equal);
+    assert!(Range::new("1-10").unwrap() < Range::new("1-11").unwrap());
+
+    // 'a' is stored as 97 but letters still come after numbers
+    assert!(Range::new("100").unwrap() < Range::new("a").unwrap());
+    assert!(Range::new("1-200").unwrap() < Range::new("a-c").unwrap());
+}
+
+#[test]
+fn testing_range_size_hint() {
+    let mut range = Range::new("1-10/3").unwrap();
+    assert_eq!(range.size_hint(), (4, Some(4)));
+    range.next();
+    range.next();
+    assert_eq!(range.size_hint(), (2, Some(2)));
+    assert_eq!(range.clone().count(), 2);
+    range.next();
+    range.next();
+    assert_eq!(range.size_hint(), (0, Some(0)));
+    assert_eq!(range.next(), None);
+    assert_eq!(range.next(), None);
+
+    let mut range = Range::new("9-1/2").unwrap();
+    range.next();
+    assert_eq!(range.size_hint(), (4, Some(4)));
+    assert_eq!(range.count(), 4);
+
+    let range = Range::new("18446744073709551610-18446744073709551615/4").unwrap();
+    assert_eq!(range.size_hint(), (2, Some(2)));
+    assert_eq!(range.collect::<Vec<String>>().len(), 2);
+
+    // The iteration ends even when the Range covers the whole u64 space
+    let mut range = Range::new("0-18446744073709551615/18446744073709551615").unwrap();
+    assert_eq!(range.len(), 2);
+    assert_eq!(range.get_next(), Some(0));
+    assert_eq!(range.get_next(), Some(18446744073709551615));
+    assert_eq!(range.get_next(), None);
+    assert_eq!(range.size_hint(), (0, Some(0)));
+    range.reset();
+    assert_eq!(range.count(), 2);
+
+    let range = Range::new("18446744073709551615-0/18446744073709551615").unwrap();
+    assert_eq!(range.collect::<Vec<String>>(), vec!["18446744073709551615", "0"]);
+}
+
+#[test]
+fn testing_range_iter_cow() {
+    let range = Range::new("1-1000").unwrap();
+    let values: Vec<String> = range.iter_cow().map(|v| v.into_owned()).collect();
+    assert_eq!(values, range.clone().collect::<Vec<String>>());
+    assert!(matches!(range.iter_cow().next(), Some(Cow::Borrowed("1"))));
+
+    let range = Range::new("008-1030/511").unwrap();
+    assert_eq!(range.iter_cow().collect::<Vec<Cow<str>>>(), vec!["008", "519", "1030"]);
+}
+
+#[test]
+fn testing_range_operations_iterate_from_start() {
+    let range_a: Range = "02-40/2".parse().unwrap();
+    let range_b: Range = "60-20/3".parse().unwrap();
+    let inter = range_a.intersection(&range_b).pop().unwrap();
+    assert_eq!(inter.get_current(), 24);
+    assert_eq!(inter.collect::<Vec<String>>(), vec!["24", "30", "36"]);
+
+    let range_a: Range = "1-20".parse().unwrap();
+    let range_b: Range = "2-40/2".parse().unwrap();
+    for range in range_a.union(&range_b).into_iter().chain(range_a.difference(&range_b)) {
+        assert_eq!(range.get_current(), range.start());
+        assert_eq!(range.clone().collect::<Vec<String>>(), range.generate_values().iter().map(|v| v.to_string()).collect::<Vec<String>>());
+    }
+}
+
+#[test]
+fn testing_range_symmetric_difference() {
+    let range_a: Range = "1-10".parse().unwrap();
+    let range_b: Range = "5-15".parse().unwrap();
+    let sym = range_a.symmetric_difference(&range_b);
+    assert_eq!(sym, vec![Range::new("1-4").unwrap(), Range::new("11-15").unwrap()]);
+    assert_eq!(sym, range_b.symmetric_difference(&range_a));
+
+    let range_a: Range = "1-13/3".parse().unwrap();
+    // 1 4 7 10 13
+    let range_b: Range = "7-19/3".parse().unwrap();
+    // 7 10 13 16 19
+    let sym = range_a.symmetric_difference(&range_b);
+    assert_eq!(sym, vec![Range::new("1-4/3").unwrap(), Range::new("16-19/3").unwrap()]);
+
+    let range_a: Range = "2-20/2".parse().unwrap();
+    let range_b: Range = "20-2/2".parse().unwrap();
+    assert!(range_a.symmetric_difference(&range_b).is_empty());
+}
+
+#[test]
+fn testing_range_fold_max_step() {
+    let folded = fold_values_in_vec_range(vec![1, 100], 0);
+    assert_eq!(folded.iter().map(|r| r.to_string()).collect::<Vec<String>>(), vec!["1-100/99"]);
+
+    let folded = fold_values_in_vec_range_max_step(vec![1, 100], 0, 10);
+    assert_eq!(folded.iter().map(|r| r.to_string()).collect::<Vec<String>>(), vec!["1", "100"]);
+
+    let folded = fold_values_in_vec_range_max_step(vec![1, 100], 0, 99);
+    assert_eq!(folded.iter().map(|r| r.to_string()).collect::<Vec<String>>(), vec!["1-100/99"]);
+
+    let folded = fold_values_in_vec_range_max_step(vec![1, 2, 3, 50, 70, 90], 2, 10);
+    assert_eq!(folded.iter().map(|r| r.to_string()).collect::<Vec<String>>(), vec!["01-03", "50", "70", "90"]);
+}
+
+#[test]
+fn testing_range_colon_separator() {
+    assert_eq!(Range::new("1:10").unwrap(), Range::new("1-10").unwrap());
+    assert_eq!(Range::new("10:01/3").unwrap().to_string(), "10-01/3");
+    assert!(Range::new("1:10-12").is_err());
+    assert!(Range::new("1:10:12").is_err());
+}
+
+#[test]
+fn testing_range_letters() {
+    let value = get_range_values_from_str("a-c");
+    assert_eq!(value, vec!["a", "b", "c"]);
+
+    let value = get_range_values_from_str("c-a");
+    assert_eq!(value, vec!["c", "b", "a"]);
+
+    let value = get_range_values_from_str("A-G/3");
+    assert_eq!(value, vec!["A", "D", "G"]);
+
+    let range = Range::new("H").unwrap();
+    assert!(range.is_alpha());
+    assert_eq!(range.to_string(), "H");
+    assert_eq!(Range::new("z-x").unwrap().to_string(), "z-x");
+    assert_ne!(Range::new("a-c").unwrap(), Range::new("97-99").unwrap());
+    assert_eq!(Range::new("a-z").unwrap().len(), 26);
+
+    assert!(Range::new("a-5").is_err());
+    assert!(Range::new("1-f").is_err());
+    assert!(Range::new("a-F").is_err());
+    assert!(Range::new("ab-f").is_err());
+
+    let range_a = Range::new("X-Z").unwrap();
+    let range_b = Range::new("a-c").unwrap();
+    let union: Vec<String> = range_a.union(&range_b).iter().map(|r| r.to_string()).collect();
+    assert_eq!(union, vec!["X-Z", "a-c"]);
+}
+
+#[test]
+fn testing_range_complement_within() {
+    let range: Range = "5-8".parse().unwrap();
+    assert_eq!(range.complement_within(1, 10), vec![Range::new("1-4").unwrap(), Range::new("9-10").unwrap()]);
+    assert_eq!(range.complement_within(5, 8), vec![]);
+    assert_eq!(range.complement_within(10, 1), vec![]);
+
+    // The Range goes beyond the bounds
+    let range: Range = "0-20/2".parse().unwrap();
+    assert_eq!(range.complement_within(5, 9), vec![Range::new("5-9/2").unwrap()]);
+
+    let range: Range = "05-15".parse().unwrap();
+    let complement: Vec<String> = range.complement_within(1, 20).iter().map(|r| r.to_string()).collect();
+    assert_eq!(complement, vec!["01-04", "16-20"]);
+}
+
+#[test]
+fn testing_range_hash() {
+    use std::collections::HashSet;
+
+    let mut ranges = HashSet::new();
+    ranges.insert(Range::new("1-10").unwrap());
+    ranges.insert(Range::new("01-10").unwrap());
+    ranges.insert(Range::new("001-010").unwrap());
+    assert_eq!(ranges.len(), 1);
+
+    ranges.insert(Range::new("1-10/2").unwrap());
+    assert_eq!(ranges.len(), 2);
+}
+
+#[test]
+fn testing_range_to_string_directional() {
+    for string in ["10-1/2", "1-10/2", "010-001/3", "001-010/3", "7", "9-8", "z-a/5", "a-z/5"] {
+        let range = Range::new(string).unwrap();
+        let directional = range.to_string_directional();
+        assert_eq!(directional, string);
+
+        let back = Range::new(&directional).unwrap();
+        assert_eq!(back, range);
+        assert_eq!(back.is_reverse_order(), range.is_reverse_order());
+        assert_eq!(back.generate_values(), range.generate_values());
+    }
+
+    let reversed = Range::new("1-10/4").unwrap().new_range_reversed();
+    assert_eq!(reversed.to_string_directional(), "9-1/4");
+    assert_eq!(Range::new(&reversed.to_string_directional()).unwrap(), reversed);
+}
+
+#[test]
+fn testing_range_with_step() {
+    let range: Range = "1-100".parse().unwrap();
+    let resampled = range.with_step(25).unwrap();
+    assert_eq!(resampled.to_string(), "1-100/25");
+    assert_eq!(resampled.collect::<Vec<String>>(), vec!["1", "26", "51", "76"]);
+
+    let range: Range = "010-001".parse().unwrap();
+    assert_eq!(range.with_step(3).unwrap().collect::<Vec<String>>(), vec!["010", "007", "004", "001"]);
+
+    assert!(range.with_step(0).is_err());
+}
+
+#[test]
+fn testing_range_iter() {
+    let range = Range::new("08-12/2").unwrap();
+    assert_eq!(range.iter().collect::<Vec<String>>(), vec!["08", "10", "12"]);
+    assert_eq!(range.iter().collect::<Vec<String>>(), vec!["08", "10", "12"]);
+    assert_eq!(range.iter().size_hint(), (3, Some(3)));
+
+    let mut values = vec![];
+    for value in &range {
+        values.push(value);
+    }
+    assert_eq!(values, vec!["08", "10", "12"]);
+
+    // The iterator of the Range itself is not affected
+    let mut range = Range::new("c-a").unwrap();
+    assert_eq!(range.next(), Some("c".to_string()));
+    assert_eq!(range.iter().collect::<Vec<String>>(), vec!["c", "b", "a"]);
+    assert_eq!(range.next(), Some("b".to_string()));
+}
+
+#[test]
+fn testing_range_intersection_values() {
+    let ranges = ["1-100/4", "1-100/6", "3-100/10", "100-1/7", "02-40/2", "60-20/3", "5", "a-z/2", "z-a/3"];
+
+    for a in ranges {
+        for b in ranges {
+            let range_a: Range = a.parse().unwrap();
+            let range_b: Range = b.parse().unwrap();
+            if range_a.is_alpha() != range_b.is_alpha() {
+                continue;
+            }
+
+            let second = range_b.generate_values();
+            let mut expected: Vec<u64> = range_a.generate_values().into_iter().filter(|v| second.contains(v)).collect();
+            expected.sort_unstable();
+
+            let values: Vec<u64> = range_a.intersection(&range_b).iter().flat_map(|r| r.generate_values()).collect();
+            assert_eq!(values, expected, "{a} and {b}");
+        }
+    }
+
+    let range_a: Range = "1-100/4".parse().unwrap();
+    let range_b: Range = "1-100/6".parse().unwrap();
+    assert_eq!(range_a.intersection(&range_b), vec!["1-97/12".parse::<Range>().unwrap()]);
+}
+
+#[test]
+fn testing_fold_unsorted_and_duplicates() {
+    let folded = fold_values_in_vec_range(vec![5, 1, 3], 0);
+    assert_eq!(folded, vec![Range::new("1-5/2").unwrap()]);
+
+    let folded = fold_values_in_vec_range(vec![1, 1, 2], 0);
+    assert_eq!(folded, vec![Range::new("1-2").unwrap()]);
+
+    let folded = fold_values_in_vec_range(vec![9, 2, 9, 1, 20, 3], 0);
+    let values: Vec<u64> = folded.iter().flat_map(|r| r.generate_values()).collect();
+    assert_eq!(values, vec![1, 2, 3, 9, 20]);
+
+    assert!(fold_values_in_vec_range(vec![], 0).is_empty());
+}
+
+#[test]
+#[allow(deprecated)]
+fn testing_deprecated_u32_names() {
+    let range: Range = "1-10/3".parse().unwrap();
+    assert_eq!(range.generate_vec_u32(), range.generate_values());
+    assert_eq!(vec_u32_intersection(vec![1, 2, 3], vec![2, 3, 4]), values_intersection(vec![1, 2, 3], vec![2, 3, 4]));
+    assert_eq!(fold_vec_u32_in_vec_range(vec![1, 2, 3], 0), fold_values_in_vec_range(vec![1, 2, 3], 0));
+    assert_eq!(fold_vec_u32_in_vec_range_max_step(vec![1, 100], 0, 10), fold_values_in_vec_range_max_step(vec![1, 100], 0, 10));
+}
+
+#[test]
+fn testing_range_overlaps() {
+    // overlapping and compatible
+    let range_a: Range = "1-99/2".parse().unwrap();
+    assert!(range_a.overlaps(&"2-100/3".parse().unwrap()));
+    assert!(range_a.overlaps(&"50-60".parse().unwrap()));
+    assert!(range_a.overlaps(&"99".parse().unwrap()));
+    assert!(range_a.overlaps(&"100-1/3".parse().unwrap()));
+
+    // overlapping but incompatible
+    assert!(!range_a.overlaps(&"2-100/2".parse().unwrap()));
+    assert!(!range_a.overlaps(&"10-20/10".parse().unwrap()));
+    let range_b: Range = "1-10/6".parse().unwrap();
+    assert!(!range_b.overlaps(&"2-11/4".parse().unwrap()));
+
+    // disjoint
+    assert!(!range_a.overlaps(&"100-200".parse().unwrap()));
+    assert!(!"1-10".parse::<Range>().unwrap().overlaps(&"11-20".parse().unwrap()));
+
+    // wide ranges are not expanded
+    let range_a: Range = "0-18446744073709551614/2".parse().unwrap();
+    let range_b: Range = "1-18446744073709551615/2".parse().unwrap();
+    assert!(!range_a.overlaps(&range_b));
+    assert!(range_a.overlaps(&"18446744073709551610-18446744073709551615/5".parse().unwrap()));
+
+    // huge steps do not overflow
+    let range_a: Range = "1-18446744073709551615/18446744073709551557".parse().unwrap();
+    let range_b: Range = "2-18446744073709551615/18446744073709551533".parse().unwrap();
+    assert!(!range_a.overlaps(&range_b));
+    let range_a: Range = "0-18446744073709551615/18446744073709551557".parse().unwrap();
+    let range_b: Range = "18446744073709551557-1/18446744073709551533".parse().unwrap();
+    assert!(range_a.overlaps(&range_b));
+
+    // same results as the intersection
+    let ranges = ["1-100/4", "1-100/6", "3-100/10", "100-1/7", "02-40/2", "60-20/3", "5", "7-9"];
+    for a in ranges {
+        for b in ranges {
+            let range_a: Range = a.parse().unwrap();
+            let range_b: Range = b.parse().unwrap();
+            assert_eq!(range_a.overlaps(&range_b), !range_a.intersection(&range_b).is_empty(), "{a} and {b}");
+        }
+    }
+}
+
+#[test]
+fn testing_range_intersection_wide_ranges() {
+    let range_a: Range = "0-1000000".parse().unwrap();
+    let range_b: Range = "0-1000000/2".parse().unwrap();
+    assert_eq!(range_a.intersection(&range_b), vec![range_b.clone()]);
+
+    // Expanding these ones would never end
+    let range_a: Range = "0-18446744073709551615/3".parse().unwrap();
+    let range_b: Range = "18446744073709551615-1/4".parse().unwrap();
+    let inter = range_a.intersection(&range_b);
+    assert_eq!(inter, vec!["3-18446744073709551615/12".parse::<Range>().unwrap()]);
+    assert_eq!(inter[0].len(), 1537228672809129302);
+
+    // Steps close to u64::MAX must not overflow
+    let range_a: Range = "1-18446744073709551615/18446744073709551557".parse().unwrap();
+    let range_b: Range = "2-18446744073709551615/18446744073709551533".parse().unwrap();
+    assert!(range_a.intersection(&range_b).is_empty());
+    let range_a: Range = "0-18446744073709551615/18446744073709551557".parse().unwrap();
+    let range_b: Range = "18446744073709551557-1/18446744073709551533".parse().unwrap();
+    assert_eq!(range_a.intersection(&range_b), vec!["18446744073709551557".parse::<Range>().unwrap()]);
+    let range_a: Range = "0-18446744073709551615/18446744073709551615".parse().unwrap();
+    assert_eq!(range_a.intersection(&range_a.clone()), vec![range_a.clone()]);
+}
@@ -20,10 +20,12 @@
  *  Inc., 59 Temple Place - Suite 330, Boston, MA 02111-1307, USA.
  */
 
-use crate::range::{fold_vec_u32_in_vec_range, vec_u32_intersection, Range};
+use crate::range::{fold_values_in_vec_range, fold_vec_in_vec_range, values_intersection, values_symmetric_difference, Range};
+use std::collections::HashSet;
 use std::error::Error;
 use std::fmt;
 use std::fmt::Write;
+use std::hash::{Hash, Hasher};
 use std::str::FromStr;
 
 #[cfg(test)]
@@ -34,8 +36,8 @@ use std::process::exit; //used for testing
 /// kept when generating numbers with the iterator.
 /// * `set` is the vector of Range. It may be empty.
 /// * `curr` is used remember the current index in the vector of Ranges
-///      and is used to calculate next number in RangeSet iterator's
-///      implementation.
+///   and is used to calculate next number in RangeSet iterator's
+///   implementation.
 ///
 /// RangeSet examples:
 /// * "1,3-5,89"
@@ -53,6 +55,12 @@ pub struct RangeSet {
 }
 
 impl RangeSet {
+    /// Returns the Ranges the RangeSet is made of: `1,3-5,89` is made
+    /// of the Ranges `1`, `3-5` and `89`.
+    pub fn ranges(&self) -> &[Range] {
+        &self.set
+    }
+
     /// True when we only have one member and not a set ie: node003
     pub fn is_alone(&self) -> bool {
         self.set.len() == 1 && self.set[0].start_is_end() && self.set[0].step_is_one()
@@ -65,52 +73,365 @@ impl RangeSet {
         }
     }
 
-    pub fn get_current(&self) -> (u32, usize) {
+    pub fn get_current(&self) -> (u64, usize) {
         let index = self.curr;
         let pad = self.set[index].get_pad();
 
         (self.set[index].get_current(), pad)
     }
 
-    /// Counts the number of elements in the rangeset
-    pub fn len(&self) -> u32 {
+    /// Counts the number of elements in the rangeset. The count saturates
+    /// at u64::MAX instead of overflowing.
+    pub fn len(&self) -> u64 {
         if self.set.is_empty() {
             0
         } else {
-            let mut total = 0;
+            let mut total: u64 = 0;
             for r in self.set.iter() {
-                total += r.len();
+                total = total.saturating_add(r.len());
             }
             total
         }
     }
 
+    /// Counts the number of Ranges in the rangeset: `1-10,15,20-30/2` has 3 Ranges.
+    pub fn range_count(&self) -> usize {
+        self.set.len()
+    }
+
+    /// Tells whether the values of the RangeSet fold well into Ranges.
+    /// It is false when, once sorted and folded, most of the values end
+    /// in Ranges of one or two values: `1,2,4,8` gives `1-2,4-8/4` and
+    /// is not considered foldable whereas `1-8` is.
+    pub fn is_foldable(&self) -> bool {
+        let mut values: Vec<u64> = Vec::new();
+        for r in &self.set {
+            values.append(&mut r.generate_values());
+        }
+        if values.is_empty() {
+            return true;
+        }
+        values.sort_unstable();
+        values.dedup();
+
+        let total = values.len() as u64;
+        let folded: u64 = fold_values_in_vec_range(values, 0).iter().map(|r| r.len()).filter(|len| *len > 2).sum();
+
+        2 * folded >= total
+    }
+
+    /// True when the RangeSet is made of letters such as `a-c,x`.
+    pub fn is_alpha(&self) -> bool {
+        self.set.first().is_some_and(|r| r.is_alpha())
+    }
+
+    /// Formats a value of the RangeSet: a letter if the RangeSet is
+    /// made of letters or the number padded with `pad`.
+    pub(crate) fn format_value(&self, value: u64, pad: usize) -> String {
+        match u8::try_from(value) {
+            Ok(letter) if self.is_alpha() => char::from(letter).to_string(),
+            _ => format!("{value:0pad$}"),
+        }
+    }
+
     /// Tells whether a RangeSet is empty or not.
     pub fn is_empty(&self) -> bool {
         self.set.is_empty()
     }
 
+    /// Returns an iterator over the values of the RangeSet in reverse
+    /// order: from the last value of the last Range to the first value
+    /// of the first Range (`1-3,10-12` gives `12,11,10,3,2,1`). The
+    /// RangeSet itself is not modified.
+    pub fn rev_iter(&self) -> impl Iterator<Item = String> + '_ {
+        self.set.iter().rev().flat_map(|range| (0..range.len()).rev().filter_map(move |n| range.nth_value(n)).map(move |v| range.format_value(v)))
+    }
+
+    /// Returns a new RangeSet generating the values of self in reverse
+    /// order: `1-3,10-12` gives `12-10,3-1`.
+    pub fn reversed(&self) -> RangeSet {
+        RangeSet {
+            set: self.set.iter().rev().map(|range| range.new_range_reversed()).collect(),
+            curr: 0,
+        }
+    }
+
+    /// Merges consecutive single value Ranges into one Range: `21,22,23,30`
+    /// becomes `21-23,30`. Values and their order are kept. The iterator
+    /// is reset.
+    pub fn compact(&mut self) {
+        let mut compacted: Vec<Range> = Vec::new();
+        let mut index = 0;
+
+        // gives the value of a single value Range
+        let single = |r: &Range| {
+            if r.len() == 1 {
+                r.nth_value(0)
+            } else {
+                None
+            }
+        };
+
+        while index < self.set.len() {
+            let mut last = index;
+            while let (Some(value), Some(next)) = (single(&self.set[last]), self.set.get(last + 1).and_then(single)) {
+                if value.checked_add(1) != Some(next) {
+                    break;
+                }
+                last += 1;
+            }
+
+            if last == index {
+                compacted.push(self.set[index].clone());
+            } else {
+                let start = single(&self.set[index]).unwrap();
+                let end = single(&self.set[last]).unwrap();
+                let pad = self.set[index..=last].iter().map(|r| r.get_pad()).max().unwrap_or(0);
+                compacted.extend(fold_vec_in_vec_range((start..=end).collect(), pad, self.is_alpha()));
+            }
+            index = last + 1;
+        }
+
+        self.set = compacted;
+        self.reset();
+    }
+
+    /// Appends the ranges of `other` at the end of self RangeSet.
+    /// Order is kept and nothing is merged. The iterator is reset.
+    pub fn extend_from(&mut self, other: &RangeSet) {
+        self.set.extend(other.set.iter().cloned());
+        self.reset();
+    }
+
+    /// Folds the RangeSet as a hostlist: values are sorted, deduplicated
+    /// and only consecutive values are folded (no step) ie: `9-1/2,4`
+    /// gives `1,3-5,7,9`.
+    pub fn to_hostlist(&self) -> String {
+        let mut values: Vec<u64> = Vec::new();
+        let mut pad: usize = 0;
+
+        for r in &self.set {
+            pad = pad.max(r.get_pad());
+            values.append(&mut r.generate_values());
+        }
+        values.sort_unstable();
+        values.dedup();
+
+        let mut hostlist: Vec<String> = Vec::new();
+        let mut index = 0;
+        while index < values.len() {
+            let start = values[index];
+            while index + 1 < values.len() && values[index + 1] == values[index] + 1 {
+                index += 1;
+            }
+            let end = values[index];
+            if start == end {
+                hostlist.push(self.format_value(start, pad));
+            } else {
+                hostlist.push(format!("{}-{}", self.format_value(start, pad), self.format_value(end, pad)));
+            }
+            index += 1;
+        }
+
+        hostlist.join(",")
+    }
+
     /// Union of self RangeSet with other RangeSet:
     ///  `1,3-5,89` and `9-2,101,2-8/2` -> 1-9,89,101
     pub fn union(&self, other: &Self) -> RangeSet {
-        let mut united: Vec<u32> = Vec::new();
+        let mut united: Vec<u64> = Vec::new();
         let mut pad: usize = 0;
 
         for r in &self.set {
             pad = pad.max(r.get_pad());
-            united.append(&mut r.generate_vec_u32());
+            united.append(&mut r.generate_values());
         }
         for r in &other.set {
             pad = pad.max(r.get_pad());
-            united.append(&mut r.generate_vec_u32());
+            united.append(&mut r.generate_values());
+        }
+
+        if united.is_empty() {
+            return RangeSet::empty();
         }
 
         united.sort_unstable();
         united.dedup();
 
         RangeSet {
-            set: fold_vec_u32_in_vec_range(united, pad),
-            curr: 1,
+            set: fold_vec_in_vec_range(united, pad, self.is_alpha() || other.is_alpha()),
+            curr: 0,
+        }
+    }
+
+    /// Difference of self RangeSet with other RangeSet: values of self
+    /// that are not in other. `1,3-5,89` and `9-2,101,2-8/2` -> `1-89/88`.
+    /// None is returned when every value of self is in other.
+    pub fn difference(&self, other: &Self) -> Option<RangeSet> {
+        // special cases where self or other is empty
+        if self.is_empty() {
+            return None;
+        } else if other.is_empty() {
+            return Some(RangeSet {
+                set: self.set.clone(),
+                curr: self.curr,
+            });
+        }
+
+        let mut first: Vec<u64> = Vec::new();
+        let mut second: Vec<u64> = Vec::new();
+        let mut pad: usize = 0;
+
+        for r in &self.set {
+            pad = pad.max(r.get_pad());
+            first.append(&mut r.generate_values());
+        }
+        for r in &other.set {
+            pad = pad.max(r.get_pad());
+            second.append(&mut r.generate_values());
+        }
+
+        first.sort_unstable();
+        first.dedup();
+        second.sort_unstable();
+        first.retain(|v| second.binary_search(v).is_err());
+
+        if first.is_empty() {
+            None
+        } else {
+            Some(RangeSet {
+                set: fold_vec_in_vec_range(first, pad, self.is_alpha()),
+                curr: 0,
+            })
+        }
+    }
+
+    /// Returns a new RangeSet with the same values as self where
+    /// overlapping and adjacent Ranges are merged and sorted in
+    /// ascending order: `5-10,1-3,2-8` gives `1-10`. Self is not modified.
+    pub fn normalize(&self) -> RangeSet {
+        let mut values: Vec<u64> = Vec::new();
+        let mut pad: usize = 0;
+
+        for r in &self.set {
+            pad = pad.max(r.get_pad());
+            values.append(&mut r.generate_values());
+        }
+
+        if values.is_empty() {
+            return RangeSet::empty();
+        }
+
+        values.sort_unstable();
+        values.dedup();
+
+        RangeSet {
+            set: fold_vec_in_vec_range(values, pad, self.is_alpha()),
+            curr: 0,
+        }
+    }
+
+    /// Returns the value at position `n` (the first value being at
+    /// position 0) formatted as the RangeSet displays it, or None if
+    /// the RangeSet is too short: the value 3 of `1-2,10-20/5` is `15`.
+    pub fn nth_str(&self, n: u64) -> Option<String> {
+        let mut n = n;
+
+        for range in &self.set {
+            match range.nth_value(n) {
+                Some(value) => return Some(range.format_value(value)),
+                None => n -= range.len(),
+            }
+        }
+        None
+    }
+
+    /// Returns a copy of the RangeSet where ranges padded with less than
+    /// `pad` digits are padded with `pad` digits: `8-10,007` with a pad
+    /// of 2 gives `08-10,007`.
+    pub(crate) fn with_min_pad(&self, pad: usize) -> RangeSet {
+        let mut rangeset = self.clone();
+        rangeset.set.iter_mut().for_each(|r| r.pad_at_least(pad));
+        rangeset
+    }
+
+    /// Tells whether `token` is one of the values of the RangeSet written
+    /// exactly as the RangeSet writes it: `3`, `c` or `007`. With `1-10`
+    /// `03` is not considered as a value whereas `3` is.
+    pub fn contains_str(&self, token: &str) -> bool {
+        let value = match token.as_bytes() {
+            [letter] if letter.is_ascii_alphabetic() => *letter as u64,
+            _ => match token.parse::<u64>() {
+                Ok(value) => value,
+                Err(_) => return false,
+            },
+        };
+
+        self.set.iter().any(|r| r.contains(value) && r.format_value(value) == token)
+    }
+
+    /// Returns the values of the RangeSet as sorted and merged closed
+    /// intervals: `10-12,1-5,3-7/2` gives `[(1, 5), (7, 7), (10, 12)]`.
+    pub fn intervals(&self) -> Vec<(u64, u64)> {
+        let mut values: Vec<u64> = self.set.iter().flat_map(|r| r.generate_values()).collect();
+        let mut intervals: Vec<(u64, u64)> = Vec::new();
+
+        values.sort_unstable();
+        values.dedup();
+        for value in values {
+            match intervals.last_mut() {
+                Some((_, end)) if *end + 1 == value => *end = value,
+                _ => intervals.push((value, value)),
+            }
+        }
+        intervals
+    }
+
+    /// Tells whether self and other have the same values whatever their
+    /// order, duplicates and the way they are folded: `1-4` and `3-4,1-2`
+    /// have the same values (but are not equal with `==`).
+    pub fn same_values(&self, other: &RangeSet) -> bool {
+        let values = |rangeset: &RangeSet| {
+            let mut values: Vec<u64> = rangeset.set.iter().flat_map(|r| r.generate_values()).collect();
+            values.sort_unstable();
+            values.dedup();
+            values
+        };
+
+        values(self) == values(other)
+    }
+
+    /// Symmetric difference of self RangeSet with other RangeSet: values
+    /// that are in exactly one of the two RangeSets. `1-10` and `5-15`
+    /// gives `1-4,11-15`. None is returned when both RangeSets have the
+    /// same values.
+    pub fn symmetric_difference(&self, other: &Self) -> Option<RangeSet> {
+        let mut first: Vec<u64> = Vec::new();
+        let mut second: Vec<u64> = Vec::new();
+        let mut pad: usize = 0;
+
+        for r in &self.set {
+            pad = pad.max(r.get_pad());
+            first.append(&mut r.generate_values());
+        }
+        for r in &other.set {
+            pad = pad.max(r.get_pad());
+            second.append(&mut r.generate_values());
+        }
+
+        first.sort_unstable();
+        first.dedup();
+        second.sort_unstable();
+        second.dedup();
+
+        let sym = values_symmetric_difference(&first, &second);
+        if sym.is_empty() {
+            None
+        } else {
+            Some(RangeSet {
+                set: fold_vec_in_vec_range(sym, pad, self.is_alpha() || other.is_alpha()),
+                curr: 0,
+            })
         }
     }
 
@@ -132,24 +453,24 @@ impl RangeSet {
         // here self and other are not empty so we get at least
         // 2 vectors.
 
-        let mut first: Vec<u32> = Vec::new();
-        let mut second: Vec<u32> = Vec::new();
+        let mut first: Vec<u64> = Vec::new();
+        let mut second: Vec<u64> = Vec::new();
         let mut pad: usize = 0;
 
         for r in &self.set {
             pad = pad.max(r.get_pad());
-            let mut v = r.generate_vec_u32();
+            let mut v = r.generate_values();
             first.append(&mut v);
         }
         for r in &other.set {
             pad = pad.max(r.get_pad());
-            let mut v = r.generate_vec_u32();
+            let mut v = r.generate_values();
             second.append(&mut v);
         }
 
-        if let Some(inter) = vec_u32_intersection(first, second) {
+        if let Some(inter) = values_intersection(first, second) {
             //println!("{:?}", inter);
-            let range_vec = fold_vec_u32_in_vec_range(inter, pad);
+            let range_vec = fold_vec_in_vec_range(inter, pad, self.is_alpha());
             //println!("{:?}", range_vec);
             Some(RangeSet {
                 set: range_vec,
@@ -160,42 +481,42 @@ impl RangeSet {
         }
     }
 
-    pub fn get_next(&mut self) -> Option<(u32, usize)> {
-        let index = self.curr;
-        let mut pad = self.set[index].get_pad();
-
-        let next = match self.set[index].get_next() {
-            Some(number) => number, // gives next number in Range range.
-            None => {
-                /* This tells us that range Range is finished : need to iter over next range. */
-                if index + 1 < self.set.len() {
-                    /* There is another Range in the vector */
-                    self.curr = index + 1;
-                    pad = self.set[self.curr].get_pad();
-                    match self.set[self.curr].get_next() {
-                        Some(number) => number,
-                        None => return None,
+    pub fn get_next(&mut self) -> Option<(u64, usize)> {
+        /* Iterates over the Ranges until one gives a number or until there is no more Range */
+        while self.curr < self.set.len() {
+            let index = self.curr;
+            let pad = self.set[index].get_pad();
+
+            match self.set[index].get_next() {
+                Some(number) => return Some((number, pad)), // gives next number in Range range.
+                None => {
+                    /* This tells us that range Range is finished : need to iter over next range. */
+                    if index + 1 < self.set.len() {
+                        self.curr = index + 1;
+                    } else {
+                        /* There is no other Range in the vector */
+                        return None;
                     }
-                } else {
-                    /* There is no other Range in the vector */
-                    return None;
                 }
-            }
-        };
-        Some((next, pad))
+            };
+        }
+        None
     }
 
-    /// "[1-5/2]" or "[1,3-5,89]" or "[9-15/3,4,9-2]"
+    /// "[1-5/2]" or "[1,3-5,89]" or "[9-15/3,4,9-2]" or "[a-c,x]".
+    /// Letters and numbers can not be mixed in a RangeSet. Empty segments
+    /// are ignored: "1,,3" gives "1,3" and "" gives an empty RangeSet.
     pub fn new(strange: &str) -> Result<RangeSet, Box<dyn Error>> {
         let mut set: Vec<Range> = Vec::new();
-        let rangeset: Vec<&str> = strange.split(',').collect();
+        // Empty segments (`1,,3` or `,5`) are ignored
+        let rangeset = strange.split(',').filter(|rs| !rs.trim().is_empty());
         let curr = 0;
 
         for rs in rangeset {
-            let range = match Range::new(rs) {
-                Ok(r) => r,
-                Err(e) => return Err(e),
-            };
+            let range = Range::new(rs)?;
+            if set.first().is_some_and(|first| first.is_alpha() != range.is_alpha()) {
+                return Err(format!("rangeset '{strange}' mixes letters and numbers").into());
+            }
             set.push(range);
         }
         Ok(RangeSet {
@@ -220,13 +541,49 @@ impl Iterator for RangeSet {
     type Item = String;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let (next_u32, pad) = match self.get_next() {
-            Some(v) => v,
-            None => return None,
-        };
+        let (next_u32, pad) = self.get_next()?;
+
+        Some(self.format_value(next_u32, pad))
+    }
+}
+
+/// Iterator over the values of a borrowed RangeSet. The RangeSet is
+/// not modified: `for value in &rangeset {...}` may be done many times.
+#[derive(Debug, Clone)]
+pub struct RangeSetIter<'a> {
+    set: &'a [Range],
+    index: usize,
+    n: u64,
+}
+
+impl Iterator for RangeSetIter<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(range) = self.set.get(self.index) {
+            if let Some(value) = range.nth_value(self.n) {
+                self.n += 1;
+                return Some(range.format_value(value));
+            }
+            self.index += 1;
+            self.n = 0;
+        }
+        None
+    }
+}
+
+/// Iterates over all the values of the RangeSet from the first one
+/// whatever the state of its own iterator is.
+impl<'a> IntoIterator for &'a RangeSet {
+    type Item = String;
+    type IntoIter = RangeSetIter<'a>;
 
-        let next = format!("{next_u32:0pad$}");
-        Some(next)
+    fn into_iter(self) -> Self::IntoIter {
+        RangeSetIter {
+            set: &self.set,
+            index: 0,
+            n: 0,
+        }
     }
 }
 
@@ -239,6 +596,24 @@ impl FromStr for RangeSet {
     }
 }
 
+/// Builds a folded RangeSet from a set of numbers: `{1, 2, 3, 7, 8}` gives `1-3,7-8`.
+/// Values are sorted and no padding is applied.
+impl From<HashSet<u64>> for RangeSet {
+    fn from(values: HashSet<u64>) -> Self {
+        if values.is_empty() {
+            return RangeSet::empty();
+        }
+
+        let mut values: Vec<u64> = values.into_iter().collect();
+        values.sort_unstable();
+
+        RangeSet {
+            set: fold_values_in_vec_range(values, 0),
+            curr: 0,
+        }
+    }
+}
+
 /// PartialEq trait for RangeSet to know if a rangeSet is equal or not
 /// to another rangeSet. curr (Iterator's position) is not taken into
 /// account. A RangeSet is equal to another one when all ranges are
@@ -257,6 +632,16 @@ impl PartialEq for RangeSet {
     }
 }
 
+impl Eq for RangeSet {}
+
+/// Hash trait for RangeSet consistent with equality: padding and
+/// iterator's position are not taken into account.
+impl Hash for RangeSet {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.set.hash(state);
+    }
+}
+
 /// Display trait for RangeSet. It will display the RangeSet in a folded way
 impl fmt::Display for RangeSet {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -419,4 +804,316 @@ fn testing_rangeset_union() {
             curr: 0
         }
     );
+    assert_eq!(inter.collect::<Vec<String>>().len(), 25);
+
+    let union = RangeSet::new("7").unwrap().union(&RangeSet::empty());
+    assert_eq!(union.collect::<Vec<String>>(), vec!["7"]);
+    assert!(RangeSet::empty().union(&RangeSet::empty()).is_empty());
+}
+
+#[test]
+fn testing_rangeset_difference() {
+    let rs_a: RangeSet = "1,3-5,89".parse().unwrap();
+    // "1", "3", "4", "5", "89"
+    let rs_b: RangeSet = "9-2,101,2-8/2".parse().unwrap();
+    // "9", "8", "7", "6", "5", "4", "3", "2", "101", "2", "4", "6", "8"
+
+    let diff = rs_a.difference(&rs_b);
+    let range_a = Range::new("1-89/88").unwrap();
+    assert_eq!(
+        diff,
+        Some(RangeSet {
+            set: vec![range_a],
+            curr: 0
+        })
+    );
+
+    let rs_a: RangeSet = "10-01/2,32-72/4".parse().unwrap();
+    // "10", "08", "06", "04", "02", "32", "36", "40", "44", "48", "52", "56", "60", "64", "68", "72"
+    let rs_b: RangeSet = "01-10,7-12/2,50-60/2".parse().unwrap();
+    // "01", "02", "03", "04", "05", "06", "07", "08", "09", "10", "7", "9", "11"
+
+    let diff = rs_a.difference(&rs_b).unwrap();
+    assert_eq!(diff.to_string(), "32-48/4,64-72/4");
+    assert_eq!(diff.get_current(), (32, 2));
+
+    assert_eq!(rs_a.difference(&rs_a), None);
+    assert_eq!(RangeSet::empty().difference(&rs_a), None);
+    assert_eq!(rs_a.difference(&RangeSet::empty()), Some(rs_a));
+}
+
+#[test]
+fn testing_rangeset_extend_from() {
+    let mut rangeset: RangeSet = "1-5".parse().unwrap();
+    let other: RangeSet = "10-12".parse().unwrap();
+    rangeset.extend_from(&other);
+    assert_eq!(rangeset, "1-5,10-12".parse().unwrap());
+    let values: Vec<String> = rangeset.collect();
+    assert_eq!(values, vec!["1", "2", "3", "4", "5", "10", "11", "12"]);
+}
+
+#[test]
+fn testing_rangeset_to_hostlist() {
+    let rangeset: RangeSet = "9-1/2,4".parse().unwrap();
+    assert_eq!(rangeset.to_hostlist(), "1,3-5,7,9".to_string());
+
+    let rangeset: RangeSet = "01-10,05".parse().unwrap();
+    assert_eq!(rangeset.to_hostlist(), "01-10".to_string());
+}
+
+#[test]
+fn testing_rangeset_exhausted_ranges() {
+    let value = get_rangeset_values_from_str("1,2,3,5,8,13");
+    assert_eq!(value, vec!["1", "2", "3", "5", "8", "13"]);
+
+    // Ranges that are already exhausted are skipped
+    let mut range_a: Range = "1".parse().unwrap();
+    let mut range_b: Range = "2".parse().unwrap();
+    range_a.next();
+    range_b.next();
+    let rangeset = RangeSet {
+        set: vec![Range::new("0").unwrap(), range_a, range_b, Range::new("3-4").unwrap()],
+        curr: 0,
+    };
+    let value: Vec<String> = rangeset.collect();
+    assert_eq!(value, vec!["0", "3", "4"]);
+
+    assert_eq!(RangeSet::empty().next(), None);
+}
+
+#[test]
+fn testing_rangeset_rev_iter() {
+    let rangeset: RangeSet = "1-3,10-12".parse().unwrap();
+    let value: Vec<String> = rangeset.rev_iter().collect();
+    assert_eq!(value, vec!["12", "11", "10", "3", "2", "1"]);
+
+    let rangeset: RangeSet = "10-01/3,15".parse().unwrap();
+    let value: Vec<String> = rangeset.rev_iter().collect();
+    assert_eq!(value, vec!["15", "01", "04", "07", "10"]);
+
+    let mut forward: Vec<String> = rangeset.clone().collect();
+    forward.reverse();
+    assert_eq!(value, forward);
+}
+
+#[test]
+fn testing_rangeset_reversed() {
+    let rangeset: RangeSet = "1-3,10-12".parse().unwrap();
+    assert_eq!(rangeset.reversed().to_string(), "12-10,3-1");
+
+    let rangeset: RangeSet = "01-10/4,20".parse().unwrap();
+    assert_eq!(rangeset.reversed().collect::<Vec<String>>(), rangeset.rev_iter().collect::<Vec<String>>());
+}
+
+#[test]
+fn testing_rangeset_is_foldable() {
+    let rangeset: RangeSet = "1,2,4,8".parse().unwrap();
+    assert!(!rangeset.is_foldable());
+
+    let rangeset: RangeSet = "1,2,4,8,16,32,64".parse().unwrap();
+    assert!(!rangeset.is_foldable());
+
+    let rangeset: RangeSet = "1-8".parse().unwrap();
+    assert!(rangeset.is_foldable());
+
+    let rangeset: RangeSet = "1,2,3,4,5,6,7,8,100".parse().unwrap();
+    assert!(rangeset.is_foldable());
+}
+
+#[test]
+fn testing_rangeset_from_hashset() {
+    let values: HashSet<u64> = HashSet::from([1, 2, 3, 7, 8]);
+    let rangeset = RangeSet::from(values);
+    assert_eq!(rangeset, "1-3,7-8".parse().unwrap());
+    assert_eq!(rangeset.to_string(), "1-3,7-8".to_string());
+
+    let rangeset = RangeSet::from(HashSet::new());
+    assert!(rangeset.is_empty());
+}
+
+#[test]
+fn testing_rangeset_compact() {
+    let mut rangeset: RangeSet = "21,22,23".parse().unwrap();
+    rangeset.compact();
+    assert_eq!(rangeset, "21-23".parse().unwrap());
+
+    let mut rangeset: RangeSet = "1-5/2,08,09,10,12,13,20-30".parse().unwrap();
+    rangeset.compact();
+    assert_eq!(rangeset.to_string(), "1-5/2,08-10,12-13,20-30".to_string());
+
+    let mut rangeset: RangeSet = "3,2,1".parse().unwrap();
+    rangeset.compact();
+    assert_eq!(rangeset, "3,2,1".parse().unwrap());
+}
+
+#[test]
+fn testing_rangeset_symmetric_difference() {
+    let rs_a: RangeSet = "1,3-5,89".parse().unwrap();
+    let rs_b: RangeSet = "9-2,101,2-8/2,89".parse().unwrap();
+    let sym = rs_a.symmetric_difference(&rs_b).unwrap();
+    assert_eq!(sym.to_string(), "1-2,6-9,101");
+
+    let rs_a: RangeSet = "1-21/4,30".parse().unwrap();
+    // 1 5 9 13 17 21 30
+    let rs_b: RangeSet = "9-33/4".parse().unwrap();
+    // 9 13 17 21 25 29 33
+    let sym = rs_a.symmetric_difference(&rs_b).unwrap();
+    assert_eq!(sym.collect::<Vec<String>>(), vec!["1", "5", "25", "29", "30", "33"]);
+
+    let rs_a: RangeSet = "1-4".parse().unwrap();
+    let rs_b: RangeSet = "3-4,1-2".parse().unwrap();
+    assert_eq!(rs_a.symmetric_difference(&rs_b), None);
+}
+
+#[test]
+fn testing_rangeset_same_values() {
+    let rs_a: RangeSet = "1-4".parse().unwrap();
+    let rs_b: RangeSet = "1-2,3-4".parse().unwrap();
+    assert!(rs_a != rs_b);
+    assert!(rs_a.same_values(&rs_b));
+
+    let rs_b: RangeSet = "4-1,2-3,1-4/3".parse().unwrap();
+    assert!(rs_a.same_values(&rs_b));
+
+    let rs_b: RangeSet = "1-5".parse().unwrap();
+    assert!(!rs_a.same_values(&rs_b));
+    assert!(RangeSet::empty().same_values(&RangeSet::empty()));
+}
+
+#[test]
+fn testing_rangeset_normalize() {
+    let rangeset: RangeSet = "5-10,1-3,2-8".parse().unwrap();
+    assert_eq!(rangeset.normalize().to_string(), "1-10");
+    assert_eq!(rangeset.to_string(), "5-10,1-3,2-8");
+
+    let rangeset: RangeSet = "9-2,101,2-8/2".parse().unwrap();
+    assert_eq!(rangeset.normalize().to_string(), "2-9,101");
+    assert_eq!(rangeset.collect::<Vec<String>>()[..3], ["9", "8", "7"]);
+
+    assert!(RangeSet::empty().normalize().is_empty());
+}
+
+#[test]
+fn testing_rangeset_get_next_single_values() {
+    let mut rangeset: RangeSet = "1,2,3,4".parse().unwrap();
+    let mut values = Vec::new();
+    while let Some((value, _)) = rangeset.get_next() {
+        values.push(value);
+    }
+    assert_eq!(values, vec![1, 2, 3, 4]);
+    assert_eq!(rangeset.get_next(), None);
+
+    rangeset.reset();
+    assert_eq!(rangeset.collect::<Vec<String>>(), vec!["1", "2", "3", "4"]);
+}
+
+#[test]
+fn testing_rangeset_borrowed_iteration() {
+    let rangeset: RangeSet = "01-03,10-6/2,7".parse().unwrap();
+    let first: Vec<String> = (&rangeset).into_iter().collect();
+    let mut second: Vec<String> = Vec::new();
+    for value in &rangeset {
+        second.push(value);
+    }
+    assert_eq!(first, vec!["01", "02", "03", "10", "8", "6", "7"]);
+    assert_eq!(first, second);
+    assert_eq!(first, rangeset.collect::<Vec<String>>());
+
+    assert_eq!((&RangeSet::empty()).into_iter().next(), None);
+}
+
+#[test]
+fn testing_rangeset_intervals() {
+    let rangeset: RangeSet = "10-12,1-5,3-7/2".parse().unwrap();
+    assert_eq!(rangeset.intervals(), vec![(1, 5), (7, 7), (10, 12)]);
+    assert!(RangeSet::empty().intervals().is_empty());
+}
+
+#[test]
+fn testing_rangeset_letters() {
+    let value = get_rangeset_values_from_str("a-c,x");
+    assert_eq!(value, vec!["a", "b", "c", "x"]);
+
+    let rangeset: RangeSet = "c-a,X".parse::<RangeSet>().unwrap();
+    assert!(rangeset.is_alpha());
+    assert_eq!(rangeset.to_string(), "c-a,X");
+    assert_eq!(rangeset.normalize().to_string(), "X,a-c");
+    assert_eq!(rangeset.to_hostlist(), "X,a-c");
+    assert_eq!(rangeset.rev_iter().collect::<Vec<String>>(), vec!["X", "a", "b", "c"]);
+
+    let other: RangeSet = "b-e".parse().unwrap();
+    assert_eq!(rangeset.union(&other).to_string(), "X,a-e");
+    assert_eq!(rangeset.intersection(&other).unwrap().to_string(), "b-c");
+
+    assert!(RangeSet::new("a-c,1-3").is_err());
+    assert!(RangeSet::new("1,b").is_err());
+
+    let mut rangeset: RangeSet = "a,b,c,F".parse().unwrap();
+    rangeset.compact();
+    assert_eq!(rangeset.to_string(), "a-c,F");
+}
+
+#[test]
+fn testing_rangeset_contains_str() {
+    let rangeset: RangeSet = "1-10/3,007,20-30".parse().unwrap();
+    assert!(rangeset.contains_str("4"));
+    assert!(rangeset.contains_str("007"));
+    assert!(rangeset.contains_str("25"));
+    assert!(!rangeset.contains_str("5"));
+    assert!(!rangeset.contains_str("8"));
+    assert!(!rangeset.contains_str("04"));
+    assert!(!rangeset.contains_str("x"));
+
+    let rangeset: RangeSet = "a-c".parse().unwrap();
+    assert!(rangeset.contains_str("b"));
+    assert!(!rangeset.contains_str("98"));
+}
+
+#[test]
+fn testing_rangeset_nth_str() {
+    let rangeset: RangeSet = "1-2,10-20/5,007".parse().unwrap();
+    assert_eq!(rangeset.nth_str(0), Some("1".to_string()));
+    assert_eq!(rangeset.nth_str(3), Some("15".to_string()));
+    assert_eq!(rangeset.nth_str(5), Some("007".to_string()));
+    assert_eq!(rangeset.nth_str(6), None);
+}
+
+#[test]
+fn testing_rangeset_hash() {
+    let mut rangesets = HashSet::new();
+    rangesets.insert(RangeSet::new("1-10,20").unwrap());
+    rangesets.insert(RangeSet::new("01-10,020").unwrap());
+    assert_eq!(rangesets.len(), 1);
+
+    let mut iterated = RangeSet::new("1-10,20").unwrap();
+    iterated.next();
+    rangesets.insert(iterated);
+    assert_eq!(rangesets.len(), 1);
+
+    rangesets.insert(RangeSet::new("1-10,21").unwrap());
+    assert_eq!(rangesets.len(), 2);
+}
+
+#[test]
+fn testing_rangeset_empty_segments() {
+    let rangeset = RangeSet::new("").unwrap();
+    assert!(rangeset.is_empty());
+    assert_eq!(rangeset, RangeSet::empty());
+
+    assert_eq!(RangeSet::new("1,,3").unwrap().to_string(), "1,3");
+    assert_eq!(RangeSet::new(",5").unwrap().to_string(), "5");
+    assert_eq!(RangeSet::new("1-4, ,8,").unwrap().to_string(), "1-4,8");
+}
+
+#[test]
+fn testing_rangeset_ranges() {
+    let rangeset: RangeSet = "1,3-5,89".parse().unwrap();
+    assert_eq!(rangeset.ranges().len(), 3);
+    assert_eq!(rangeset.ranges()[1], Range::new("3-5").unwrap());
+
+    let rangeset: RangeSet = "1-4,008-14/2".parse().unwrap();
+    let pads: Vec<usize> = rangeset.ranges().iter().map(|r| r.get_pad()).collect();
+    assert_eq!(pads, vec![0, 3]);
+
+    assert!(RangeSet::empty().ranges().is_empty());
 }
@@ -0,0 +1,87 @@
+/* -*- coding: utf8 -*-
+ *
+ *  serde.rs: Implements Serialize and Deserialize for the types of the crate
+ *
+ *  (C) Copyright 2022 Olivier Delhomme
+ *  e-mail : olivier.delhomme@free.fr
+ *
+ *  This program is free software; you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation; either version 3, or (at your option)
+ *  any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with this program; if not, write to the Free Software Foundation,
+ *  Inc., 59 Temple Place - Suite 330, Boston, MA 02111-1307, USA.
+ */
+use crate::node::Node;
+use crate::nodeset::NodeSet;
+use crate::range::Range;
+use crate::rangeset::RangeSet;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/* Each type is serialized as its folded string (`node[1-10]`) and
+ * deserialized with its FromStr implementation.
+ */
+macro_rules! impl_serde_with_string {
+    ($type:ty) => {
+        impl Serialize for $type {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $type {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let string = String::deserialize(deserializer)?;
+                string.parse().map_err(D::Error::custom)
+            }
+        }
+    };
+}
+
+impl_serde_with_string!(Range);
+impl_serde_with_string!(RangeSet);
+impl_serde_with_string!(Node);
+impl_serde_with_string!(NodeSet);
+
+#[test]
+fn test_serde_range() {
+    let range: Range = "01-10/3".parse().unwrap();
+    let json = serde_json::to_string(&range).unwrap();
+    assert_eq!(json, "\"01-10/3\"");
+    assert_eq!(serde_json::from_str::<Range>(&json).unwrap(), range);
+}
+
+#[test]
+fn test_serde_rangeset() {
+    let rangeset: RangeSet = "1-4,8-14/2,50".parse().unwrap();
+    let json = serde_json::to_string(&rangeset).unwrap();
+    assert_eq!(json, "\"1-4,8-14/2,50\"");
+    assert_eq!(serde_json::from_str::<RangeSet>(&json).unwrap(), rangeset);
+}
+
+#[test]
+fn test_serde_node() {
+    let node: Node = "rack[1-2]-node[01-10]".parse().unwrap();
+    let json = serde_json::to_string(&node).unwrap();
+    assert_eq!(json, "\"rack[1-2]-node[01-10]\"");
+    assert_eq!(serde_json::from_str::<Node>(&json).unwrap(), node);
+}
+
+#[test]
+fn test_serde_nodeset() {
+    let nodeset = NodeSet::new("node[1-10],gpu[1-4]").unwrap();
+    let json = serde_json::to_string(&vec![&nodeset]).unwrap();
+    assert_eq!(json, "[\"node[1-10],gpu[1-4]\"]");
+    assert_eq!(serde_json::from_str::<Vec<NodeSet>>(&json).unwrap(), vec![nodeset]);
+
+    assert!(serde_json::from_str::<NodeSet>("\"node[1-\"").is_err());
+    assert!(serde_json::from_str::<NodeSet>("42").is_err());
+}